@@ -30,7 +30,7 @@ use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 // ============================================================================
 
 /// SDK protocol version (must match server)
-pub const SDK_PROTOCOL_VERSION: &str = "1.0.0";
+pub const SDK_PROTOCOL_VERSION: &str = "1.1.0";
 
 /// Minimum supported server protocol version
 pub const MIN_SERVER_VERSION: &str = "1.0.0";
@@ -217,6 +217,24 @@ impl IterClient {
         Ok(response)
     }
 
+    /// Check that the server is answering (no runtime access on the server side)
+    pub fn ping(&mut self) -> Result<()> {
+        let response = self.send("ping", None)?;
+        let pong = response
+            .result
+            .as_ref()
+            .and_then(|r| r.get("pong"))
+            .and_then(|p| p.as_bool());
+        if pong == Some(true) {
+            Ok(())
+        } else {
+            Err(SdkError::RequestFailed(RpcError {
+                code: -1,
+                message: "Invalid ping response".to_string(),
+            }))
+        }
+    }
+
     /// List available tools
     pub fn tools_list(&mut self) -> Result<Vec<ToolInfo>> {
         let response = self.send("tools/list", None)?;
@@ -255,7 +273,20 @@ impl IterClient {
             "tools/call",
             Some(serde_json::json!({
                 "name": "node.query",
-                "arguments": { "node_id": node_id }
+                "arguments": { "node_id": node_id.to_string() }
+            })),
+        )?;
+
+        parse_tool_result(response)
+    }
+
+    /// Audit a node's ESV compliance
+    pub fn esv_audit(&mut self, node_id: u64) -> Result<EsvAudit> {
+        let response = self.send(
+            "tools/call",
+            Some(serde_json::json!({
+                "name": "esv.audit",
+                "arguments": { "node_id": node_id.to_string() }
             })),
         )?;
 
@@ -306,6 +337,9 @@ pub struct NodeState {
     pub energy: f64,
     pub esv_valid: bool,
     pub stability: f64,
+    /// Added in protocol 1.1.0; absent from 1.0 servers
+    #[serde(default)]
+    pub locked: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -318,6 +352,17 @@ pub struct GovernorStatus {
     pub healthy: bool,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct EsvAudit {
+    pub node_id: u64,
+    pub valid: bool,
+    pub compliance_status: String,
+    #[serde(default)]
+    pub violated_constraints: Vec<String>,
+    #[serde(default)]
+    pub margin: Option<f64>,
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -404,6 +449,19 @@ mod tests {
         assert!(!is_version_compatible("2.0.0"));
     }
 
+    #[test]
+    fn esv_audit_parses_server_shape() {
+        let audit: EsvAudit = serde_json::from_value(serde_json::json!({
+            "node_id": 1,
+            "valid": true,
+            "compliance_status": "compliant"
+        }))
+        .unwrap();
+        assert_eq!(audit.node_id, 1);
+        assert!(audit.valid);
+        assert_eq!(audit.compliance_status, "compliant");
+    }
+
     #[test]
     fn trace_context_creation() {
         let trace = TraceContext::new("test-trace");
@@ -12,7 +12,7 @@ import {
 
 describe("Protocol Version", () => {
   test("SDK_PROTOCOL_VERSION is valid", () => {
-    expect(SDK_PROTOCOL_VERSION).toBe("1.0.0");
+    expect(SDK_PROTOCOL_VERSION).toBe("1.1.0");
   });
 
   test("MIN_SERVER_VERSION is valid", () => {
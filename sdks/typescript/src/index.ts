@@ -22,7 +22,7 @@ import * as readline from "readline";
 // ============================================================================
 
 /** SDK protocol version (must match server) */
-export const SDK_PROTOCOL_VERSION = "1.0.0";
+export const SDK_PROTOCOL_VERSION = "1.1.0";
 
 /** Minimum supported server protocol version */
 export const MIN_SERVER_VERSION = "1.0.0";
@@ -133,6 +133,16 @@ export interface NodeState {
   energy: number;
   esv_valid: boolean;
   stability: number;
+  /** Added in protocol 1.1.0; absent from 1.0 servers */
+  locked?: boolean;
+}
+
+export interface EsvAudit {
+  node_id: number;
+  valid: boolean;
+  compliance_status: string;
+  violated_constraints?: string[];
+  margin?: number;
 }
 
 export interface GovernorStatus {
@@ -273,12 +283,22 @@ export class IterClient {
   async nodeQuery(nodeId: number): Promise<NodeState> {
     const response = await this.send("tools/call", {
       name: "node.query",
-      arguments: { node_id: nodeId },
+      arguments: { node_id: String(nodeId) },
     });
 
     return this.parseToolResult<NodeState>(response);
   }
 
+  /** Audit a node's ESV compliance */
+  async esvAudit(nodeId: number): Promise<EsvAudit> {
+    const response = await this.send("tools/call", {
+      name: "esv.audit",
+      arguments: { node_id: String(nodeId) },
+    });
+
+    return this.parseToolResult<EsvAudit>(response);
+  }
+
   /** Get governor status */
   async governorStatus(): Promise<GovernorStatus> {
     const response = await this.send("tools/call", {
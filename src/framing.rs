@@ -0,0 +1,112 @@
+//! Length-Prefixed Framing
+//!
+//! Optional STDIO framing for high-throughput clients: each message is the
+//! same JSON-RPC text as in line mode, preceded by its byte length as a
+//! 4-byte big-endian integer. Readers never scan for newlines, so payloads
+//! may contain them freely.
+//!
+//! The server selects it with `SCG_MCP_FRAMING=binary` in builds with the
+//! `binary_framing` feature; the helpers here are always available so
+//! clients and tests can speak the format.
+
+use std::io::{self, Read, Write};
+
+/// Largest accepted frame payload; longer length prefixes are rejected
+/// before any payload is read
+pub const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+/// Read one frame's payload.
+///
+/// Returns `Ok(None)` on a clean end of input (no bytes of a new frame).
+/// Input ending inside a frame is `UnexpectedEof`; a length prefix over
+/// [`MAX_FRAME_BYTES`] is `InvalidData`.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut prefix = [0u8; 4];
+    let mut filled = 0;
+    while filled < prefix.len() {
+        match reader.read(&mut prefix[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    let len = u32::from_be_bytes(prefix) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds {}", len, MAX_FRAME_BYTES),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+/// Write `payload` as one frame and flush
+pub fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "frame of {} bytes exceeds {}",
+                payload.len(),
+                MAX_FRAME_BYTES
+            ),
+        ));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_kb_payload_round_trips_with_exact_length() {
+        // Several KB with embedded newlines, which line framing would split
+        let note = "line\n".repeat(2_000);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "node.query", "arguments": {"node_id": "0", "note": note}},
+        });
+        let payload = serde_json::to_vec(&request).unwrap();
+        assert!(payload.len() > 8 * 1024);
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &payload).unwrap();
+        write_frame(&mut wire, b"{}").unwrap();
+        assert_eq!(wire.len(), 4 + payload.len() + 4 + 2);
+        assert_eq!(&wire[..4], &(payload.len() as u32).to_be_bytes());
+
+        let mut reader = wire.as_slice();
+        let first = read_frame(&mut reader).unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&first).unwrap(),
+            request
+        );
+        assert_eq!(read_frame(&mut reader).unwrap().unwrap(), b"{}");
+        assert!(read_frame(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn truncated_and_oversized_frames_are_errors() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"{\"id\":1}").unwrap();
+        wire.truncate(wire.len() - 1);
+        let err = read_frame(&mut wire.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let err = read_frame(&mut [0u8, 0].as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let oversized = (MAX_FRAME_BYTES as u32 + 1).to_be_bytes();
+        let err = read_frame(&mut oversized.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -34,7 +34,13 @@
 // ============================================================================
 
 pub mod caller_context;
+pub mod framing;
+pub mod lineage;
+pub mod method_metrics;
+pub mod policy;
+pub mod sanitizer;
 pub mod types;
+pub mod validation;
 
 // ============================================================================
 // Public Stub Module (demonstration mode)
@@ -48,9 +54,10 @@ pub mod substrate;
 // ============================================================================
 
 pub use types::{
-    BindEdgeParams, CreateNodeParams, ExportLineageParams, McpEdgeState, McpError,
-    McpGovernorStatus, McpLineageEntry, McpNodeState, MutateNodeParams, PropagateEdgeParams,
-    QueryNodeParams, RpcError, RpcRequest, RpcResponse, ToolInfo, ToolList,
+    AuditOutcome, BindEdgeParams, CreateNodeParams, ExportLineageParams, McpEdgeState, McpError,
+    McpEsvAudit, McpGovernorStatus, McpLineageEntry, McpNodeState, MutateNodeParams, PingResult,
+    PropagateEdgeParams, QueryNodeParams, ResponseMeta, RpcError, RpcRequest, RpcResponse,
+    ToolInfo, ToolList,
 };
 
 // ============================================================================
@@ -58,13 +65,35 @@ pub use types::{
 // ============================================================================
 
 pub use types::{
-    CompatibilityStatus, Deprecation, ProtocolVersion, MIN_SUPPORTED_MAJOR, PROTOCOL_MAJOR,
-    PROTOCOL_MINOR, PROTOCOL_PATCH, PROTOCOL_VERSION,
+    deprecation_for, CompatibilityStatus, DeprecatedItem, Deprecation, ProtocolVersion,
+    DEPRECATIONS, MIN_SUPPORTED_MAJOR, PROTOCOL_MAJOR, PROTOCOL_MINOR, PROTOCOL_PATCH,
+    PROTOCOL_VERSION,
 };
 
+// ============================================================================
+// External Policy Hook (always available)
+// ============================================================================
+
+pub use policy::{PolicyDecider, PolicyDecision};
+
+// ============================================================================
+// Response Sanitizer (always available)
+// ============================================================================
+
+pub use sanitizer::ResponseSanitizer;
+
 // ============================================================================
 // Stub Runtime (Public stub mode)
 // ============================================================================
 
+#[cfg(feature = "public_stub")]
+pub use substrate::clock::{Clock, MockClock, SystemClock};
+#[cfg(feature = "public_stub")]
+pub use substrate::config::{
+    ConfigBundle, ConfigSummary, EnergyPoolPolicy, IdStrategy, PropagationDirection,
+    StubRuntimeConfig,
+};
+#[cfg(feature = "public_stub")]
+pub use substrate::shared::{LoadLevel, SharedStubRuntime};
 #[cfg(feature = "public_stub")]
 pub use substrate::stub::StubRuntime;
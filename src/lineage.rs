@@ -0,0 +1,603 @@
+//! Lineage Export and Comparison
+//!
+//! Writes lineage exports (optionally gzip-compressed with the
+//! `lineage_gzip` feature) and provides forensic helpers for comparing two
+//! exports (e.g. before and after a suspected tampering). Entries are
+//! compared position by position; the first position where the exports
+//! disagree is the divergence point. Merkle roots and inclusion proofs let
+//! an auditor verify a single entry without the whole chain.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::types::{McpError, McpLineageEntry};
+
+/// Leading bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Chain head checksum over a lineage, given its entry checksums in order.
+///
+/// SHA-256 (hex) of `"lineage:{count}:"` followed by every entry checksum,
+/// so it can be recomputed from `lineage.get` results or an export and
+/// changes whenever any entry is altered, dropped, or appended.
+pub fn lineage_head_checksum<'a>(checksums: impl ExactSizeIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("lineage:{}:", checksums.len()));
+    for checksum in checksums {
+        hasher.update(checksum);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Merkle root over entry checksums, in sequence order.
+///
+/// Leaves are SHA-256 of `"leaf:"` + checksum and parents SHA-256 of
+/// `"node:"` + left + right (all hex). An unpaired last node is carried up a
+/// level unchanged rather than duplicated. An empty lineage has the root
+/// SHA-256 of `"merkle:empty"`.
+pub fn lineage_merkle_root(checksums: &[&str]) -> String {
+    let mut level: Vec<String> = checksums.iter().map(|c| merkle_leaf(c)).collect();
+    if level.is_empty() {
+        return hex::encode(Sha256::digest(b"merkle:empty"));
+    }
+    while level.len() > 1 {
+        level = merkle_level_up(&level);
+    }
+    level.remove(0)
+}
+
+/// Inclusion proof for the entry at `index`, or `None` if out of range
+pub fn lineage_merkle_proof(checksums: &[&str], index: usize) -> Option<MerkleProof> {
+    let checksum = checksums.get(index)?.to_string();
+    let mut level: Vec<String> = checksums.iter().map(|c| merkle_leaf(c)).collect();
+    let mut position = index;
+    let mut path = Vec::new();
+    while level.len() > 1 {
+        let sibling = position ^ 1;
+        if let Some(hash) = level.get(sibling) {
+            path.push(MerkleStep {
+                hash: hash.clone(),
+                side: if sibling < position {
+                    MerkleSide::Left
+                } else {
+                    MerkleSide::Right
+                },
+            });
+        }
+        level = merkle_level_up(&level);
+        position /= 2;
+    }
+    Some(MerkleProof {
+        sequence: index as u64,
+        checksum,
+        path,
+        root: level.remove(0),
+    })
+}
+
+/// Recompute the root from `proof`'s checksum and path; true if it equals `root`
+pub fn verify_merkle_proof(proof: &MerkleProof, root: &str) -> bool {
+    let computed = proof
+        .path
+        .iter()
+        .fold(merkle_leaf(&proof.checksum), |hash, step| match step.side {
+            MerkleSide::Left => merkle_parent(&step.hash, &hash),
+            MerkleSide::Right => merkle_parent(&hash, &step.hash),
+        });
+    computed == root
+}
+
+/// Hash adjacent pairs into the next level, carrying an unpaired last node up
+fn merkle_level_up(level: &[String]) -> Vec<String> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => merkle_parent(left, right),
+            _ => pair[0].clone(),
+        })
+        .collect()
+}
+
+fn merkle_leaf(checksum: &str) -> String {
+    hex::encode(Sha256::digest(format!("leaf:{}", checksum)))
+}
+
+fn merkle_parent(left: &str, right: &str) -> String {
+    hex::encode(Sha256::digest(format!("node:{}{}", left, right)))
+}
+
+/// Which side of the running hash a proof sibling sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleSide {
+    /// Sibling is the left input: `parent = H(sibling, current)`
+    Left,
+    /// Sibling is the right input: `parent = H(current, sibling)`
+    Right,
+}
+
+/// One level of a Merkle inclusion proof
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleStep {
+    /// Sibling hash at this level
+    pub hash: String,
+    /// Side the sibling sits on
+    pub side: MerkleSide,
+}
+
+/// Result of `lineage.merkle_proof`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// Sequence of the proven entry
+    pub sequence: u64,
+    /// The entry's checksum (the leaf preimage)
+    pub checksum: String,
+    /// Sibling hashes from the leaf up to the root
+    pub path: Vec<MerkleStep>,
+    /// Root the proof leads to (`lineage.merkle_root`)
+    pub root: String,
+}
+
+/// Result of `lineage.attest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineageAttestation {
+    /// Whether the current head checksum equals the expected one
+    pub matches: bool,
+    /// Current head checksum (`lineage_head_checksum`)
+    pub current_checksum: String,
+}
+
+/// Result of `write_lineage_export`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineageExport {
+    /// Number of entries written
+    pub entries: usize,
+    /// Chain head checksum of the exported entries, a receipt for `lineage.attest`
+    pub head_checksum: String,
+    /// SHA-256 (hex) of the bytes on disk (the compressed bytes when `compressed`)
+    pub checksum: String,
+    /// Whether the file is gzip-compressed
+    pub compressed: bool,
+}
+
+/// Join `relative` (already checked by `validate_relative_path`) onto the
+/// export directory `dir`, refusing any existing symlink along the way so
+/// a file can never be reached outside `dir`.
+pub fn resolve_export_path(dir: &Path, relative: &Path) -> Result<PathBuf, McpError> {
+    let mut path = dir.to_path_buf();
+    for component in relative.components() {
+        let Component::Normal(part) = component else {
+            continue;
+        };
+        path.push(part);
+        let is_symlink = std::fs::symlink_metadata(&path)
+            .map(|meta| meta.file_type().is_symlink())
+            .unwrap_or(false);
+        if is_symlink {
+            return Err(McpError::BadRequest {
+                message: "export path must not go through a symlink".to_string(),
+            });
+        }
+    }
+    Ok(path)
+}
+
+/// Write `entries` to a new file at `path` as a JSON array, gzip-compressed
+/// if `compress`.
+///
+/// Compression requires the `lineage_gzip` feature; without it a compressed
+/// export is refused as `bad_request`. An export larger than `max_bytes` on
+/// disk is refused as `capacity_exceeded` before the file is created.
+/// Existing files (symlinks included) are never replaced: `path` is
+/// claimed with `create_new`, then the content is written to a temporary
+/// sibling and renamed over the claim. Errors do not echo the path.
+pub fn write_lineage_export(
+    entries: &[McpLineageEntry],
+    path: &Path,
+    compress: bool,
+    max_bytes: u64,
+) -> Result<LineageExport, McpError> {
+    let json = serde_json::to_vec(entries).map_err(|_| McpError::SubstrateError {
+        message: "lineage export serialization failed".to_string(),
+    })?;
+    let bytes = if compress { gzip(&json)? } else { json };
+    if bytes.len() as u64 > max_bytes {
+        return Err(McpError::CapacityExceeded {
+            resource: "lineage.export".to_string(),
+            limit: max_bytes,
+        });
+    }
+    write_new_file(path, &bytes)?;
+    Ok(LineageExport {
+        entries: entries.len(),
+        head_checksum: lineage_head_checksum(entries.iter().map(|e| e.checksum.as_str())),
+        checksum: hex::encode(Sha256::digest(&bytes)),
+        compressed: compress,
+    })
+}
+
+/// Create `path` (which must not exist) holding exactly `bytes`
+fn write_new_file(path: &Path, bytes: &[u8]) -> Result<(), McpError> {
+    let failed = || McpError::SubstrateError {
+        message: "lineage export could not be written".to_string(),
+    };
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::AlreadyExists => McpError::BadRequest {
+                message: "export target already exists".to_string(),
+            },
+            _ => failed(),
+        })?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = path.with_file_name(temp_name);
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp)
+        .and_then(|mut file| file.write_all(bytes).and_then(|_| file.sync_all()))
+        .and_then(|_| std::fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+        let _ = std::fs::remove_file(path);
+        return Err(failed());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "lineage_gzip")]
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, McpError> {
+    // GzEncoder leaves the header mtime at zero, so output is reproducible
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map_err(|_| McpError::SubstrateError {
+            message: "lineage export compression failed".to_string(),
+        })
+}
+
+#[cfg(not(feature = "lineage_gzip"))]
+fn gzip(_bytes: &[u8]) -> Result<Vec<u8>, McpError> {
+    Err(McpError::BadRequest {
+        message: "compress requires a build with the lineage_gzip feature".to_string(),
+    })
+}
+
+/// Read at most `max_bytes` from `reader`; `None` on an I/O error, and
+/// `Some(Err(..))` once the cap is hit
+fn read_capped(reader: impl Read, max_bytes: u64) -> Option<Result<Vec<u8>, McpError>> {
+    let mut out = Vec::new();
+    reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut out)
+        .ok()?;
+    if out.len() as u64 > max_bytes {
+        return Some(Err(McpError::CapacityExceeded {
+            resource: "lineage export".to_string(),
+            limit: max_bytes,
+        }));
+    }
+    Some(Ok(out))
+}
+
+#[cfg(feature = "lineage_gzip")]
+fn gunzip(bytes: &[u8], max_bytes: u64) -> Option<Result<Vec<u8>, McpError>> {
+    read_capped(flate2::read::GzDecoder::new(bytes), max_bytes)
+}
+
+#[cfg(not(feature = "lineage_gzip"))]
+fn gunzip(_bytes: &[u8], _max_bytes: u64) -> Option<Result<Vec<u8>, McpError>> {
+    None
+}
+
+/// Overall outcome of a lineage comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageVerdict {
+    /// Both exports hold the same entries
+    Identical,
+    /// `b` extends `a` without changing any of its entries
+    Appended,
+    /// `b` is a strict prefix of `a`
+    Truncated,
+    /// Some entry differs at a position both exports share
+    Diverged,
+}
+
+/// Result of `diff_lineage`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineageDiff {
+    /// Comparison outcome
+    pub verdict: LineageVerdict,
+    /// Position (sequence) of the first entry that differs, if any
+    pub first_divergence: Option<u64>,
+    /// Entries from the divergence point on that appear only in `b`
+    pub added: Vec<McpLineageEntry>,
+    /// Entries from the divergence point on that appear only in `a`
+    pub removed: Vec<McpLineageEntry>,
+}
+
+/// Compare two lineage exports entry by entry.
+///
+/// Every field takes part in the comparison, so a changed checksum,
+/// operation, or tick is caught. `added`/`removed` only cover the tail
+/// after the first divergence, matched by sequence number: an entry is
+/// listed unless the other side holds an identical entry at that sequence.
+pub fn diff_lineage(a: &[McpLineageEntry], b: &[McpLineageEntry]) -> LineageDiff {
+    let shared = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let verdict = match (shared == a.len(), shared == b.len()) {
+        (true, true) => LineageVerdict::Identical,
+        (true, false) => LineageVerdict::Appended,
+        (false, true) => LineageVerdict::Truncated,
+        (false, false) => LineageVerdict::Diverged,
+    };
+    let (a_tail, b_tail) = (&a[shared..], &b[shared..]);
+    let only_in = |side: &[McpLineageEntry], other: &[McpLineageEntry]| {
+        let by_sequence: HashMap<u64, &McpLineageEntry> =
+            other.iter().map(|entry| (entry.sequence, entry)).collect();
+        side.iter()
+            .filter(|entry| by_sequence.get(&entry.sequence) != Some(entry))
+            .cloned()
+            .collect()
+    };
+    LineageDiff {
+        verdict,
+        first_divergence: (verdict != LineageVerdict::Identical).then_some(shared as u64),
+        added: only_in(b_tail, a_tail),
+        removed: only_in(a_tail, b_tail),
+    }
+}
+
+/// Load a lineage export: a JSON array of entries, or one entry per line (JSONL).
+///
+/// Gzip-compressed exports are read transparently with the `lineage_gzip`
+/// feature. Both the file and its inflated content are capped at
+/// `max_bytes` (`capacity_exceeded` beyond that), so a gzip bomb cannot
+/// exhaust memory. Errors do not echo file contents.
+pub fn load_lineage_export(path: &Path, max_bytes: u64) -> Result<Vec<McpLineageEntry>, McpError> {
+    let unreadable = || McpError::BadRequest {
+        message: "lineage export is unreadable or malformed".to_string(),
+    };
+    let file = std::fs::File::open(path).map_err(|_| unreadable())?;
+    let mut bytes = read_capped(file, max_bytes).ok_or_else(unreadable)??;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        bytes = gunzip(&bytes, max_bytes).ok_or_else(unreadable)??;
+    }
+    let raw = String::from_utf8(bytes).map_err(|_| unreadable())?;
+    if raw.trim_start().starts_with('[') {
+        return serde_json::from_str(&raw).map_err(|_| unreadable());
+    }
+    raw.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|_| unreadable()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(len: u64) -> Vec<McpLineageEntry> {
+        (0..len)
+            .map(|sequence| McpLineageEntry {
+                sequence,
+                operation: "decision".to_string(),
+                checksum: format!("{:064x}", sequence),
+                tick: sequence,
+                detail: Some("node.create".to_string()),
+                timestamp_unix_ms: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tampered_copy_diverges_at_the_edited_entry() {
+        let original = export(5);
+        let mut tampered = original.clone();
+        tampered[3].checksum = "0".repeat(64);
+
+        let diff = diff_lineage(&original, &tampered);
+
+        assert_eq!(diff.verdict, LineageVerdict::Diverged);
+        assert_eq!(diff.first_divergence, Some(3));
+        assert_eq!(diff.removed, vec![original[3].clone()]);
+        assert_eq!(diff.added, vec![tampered[3].clone()]);
+    }
+
+    #[test]
+    fn prefix_relationships_are_classified() {
+        let short = export(3);
+        let long = export(5);
+
+        assert_eq!(
+            diff_lineage(&short, &short).verdict,
+            LineageVerdict::Identical
+        );
+        assert_eq!(diff_lineage(&short, &short).first_divergence, None);
+        let appended = diff_lineage(&short, &long);
+        assert_eq!(appended.verdict, LineageVerdict::Appended);
+        assert_eq!(appended.first_divergence, Some(3));
+        assert_eq!(appended.added.len(), 2);
+        assert_eq!(
+            diff_lineage(&long, &short).verdict,
+            LineageVerdict::Truncated
+        );
+    }
+
+    #[test]
+    fn plain_export_round_trips_with_on_disk_checksum() {
+        let path = std::env::temp_dir().join(format!("iter-export-{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let entries = export(4);
+
+        let report = write_lineage_export(&entries, &path, false, u64::MAX).unwrap();
+        let on_disk = std::fs::read(&path).unwrap();
+        assert_eq!(report.checksum, hex::encode(Sha256::digest(&on_disk)));
+        assert!(!report.compressed);
+        assert_eq!(load_lineage_export(&path, u64::MAX).unwrap(), entries);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(feature = "lineage_gzip")]
+    #[test]
+    fn compressed_export_matches_uncompressed_content() {
+        use std::io::Read;
+
+        let dir = std::env::temp_dir();
+        let plain = dir.join(format!("iter-export-plain-{}.json", std::process::id()));
+        let packed = dir.join(format!("iter-export-gz-{}.json.gz", std::process::id()));
+        let entries = export(64);
+        std::fs::remove_file(&plain).ok();
+        std::fs::remove_file(&packed).ok();
+
+        write_lineage_export(&entries, &plain, false, u64::MAX).unwrap();
+        let report = write_lineage_export(&entries, &packed, true, u64::MAX).unwrap();
+        assert!(report.compressed);
+        let compressed = std::fs::read(&packed).unwrap();
+        assert_eq!(report.checksum, hex::encode(Sha256::digest(&compressed)));
+
+        let mut inflated = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut inflated)
+            .unwrap();
+        assert_eq!(inflated, std::fs::read(&plain).unwrap());
+        assert_eq!(load_lineage_export(&packed, u64::MAX).unwrap(), entries);
+        std::fs::remove_file(&plain).ok();
+        std::fs::remove_file(&packed).ok();
+    }
+
+    #[cfg(not(feature = "lineage_gzip"))]
+    #[test]
+    fn compression_is_refused_without_the_feature() {
+        let path = std::env::temp_dir().join(format!("iter-export-nogz-{}", std::process::id()));
+        let result = write_lineage_export(&export(1), &path, true, u64::MAX);
+        assert!(matches!(result, Err(McpError::BadRequest { .. })));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn oversized_input_is_refused_when_loading() {
+        let path = std::env::temp_dir().join(format!("iter-export-big-{}", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        write_lineage_export(&export(50), &path, false, u64::MAX).unwrap();
+
+        let capped = load_lineage_export(&path, 256);
+        assert!(matches!(
+            capped,
+            Err(McpError::CapacityExceeded { limit: 256, .. })
+        ));
+        assert_eq!(load_lineage_export(&path, u64::MAX).unwrap().len(), 50);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(feature = "lineage_gzip")]
+    #[test]
+    fn gzip_bomb_is_refused_while_inflating() {
+        let path = std::env::temp_dir().join(format!("iter-export-bomb-{}", std::process::id()));
+        let zeros = vec![b' '; 4 * 1024 * 1024];
+        let bomb = gzip(&zeros).unwrap();
+        assert!(bomb.len() < 64 * 1024);
+        std::fs::write(&path, &bomb).unwrap();
+
+        let result = load_lineage_export(&path, 64 * 1024);
+        assert!(matches!(result, Err(McpError::CapacityExceeded { .. })));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn existing_files_are_never_overwritten() {
+        let path = std::env::temp_dir().join(format!("iter-export-keep-{}", std::process::id()));
+        std::fs::write(&path, b"audit log").unwrap();
+
+        let result = write_lineage_export(&export(2), &path, false, u64::MAX);
+        assert!(matches!(result, Err(McpError::BadRequest { .. })));
+        assert_eq!(std::fs::read(&path).unwrap(), b"audit log");
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_targets_are_refused() {
+        let dir = std::env::temp_dir().join(format!("iter-export-dir-{}", std::process::id()));
+        let outside = std::env::temp_dir().join(format!("iter-outside-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::remove_dir_all(&outside).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("wal.jsonl"), b"wal").unwrap();
+        std::os::unix::fs::symlink(outside.join("wal.jsonl"), dir.join("link.json")).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("sub")).unwrap();
+
+        for relative in ["link.json", "sub/new.json"] {
+            let resolved = resolve_export_path(&dir, Path::new(relative));
+            assert!(matches!(resolved, Err(McpError::BadRequest { .. })));
+        }
+        // Even if a symlink appears after resolution, create_new refuses it
+        let raced = write_lineage_export(&export(1), &dir.join("link.json"), false, u64::MAX);
+        assert!(raced.is_err());
+        assert_eq!(std::fs::read(outside.join("wal.jsonl")).unwrap(), b"wal");
+        assert!(!outside.join("new.json").exists());
+
+        let fresh = resolve_export_path(&dir, Path::new("fresh.json")).unwrap();
+        write_lineage_export(&export(1), &fresh, false, u64::MAX).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::remove_dir_all(&outside).ok();
+    }
+
+    #[test]
+    fn oversized_export_is_refused_without_writing() {
+        let path = std::env::temp_dir().join(format!("iter-export-cap-{}", std::process::id()));
+        let result = write_lineage_export(&export(500), &path, false, 1024);
+        assert!(matches!(
+            result,
+            Err(McpError::CapacityExceeded { limit: 1024, .. })
+        ));
+        assert!(!path.exists());
+
+        let fits = write_lineage_export(&export(1), &path, false, 1024).unwrap();
+        assert_eq!(fits.entries, 1);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn merkle_root_is_stable_and_order_sensitive() {
+        let checksums = ["a", "b", "c", "d", "e"];
+        let root = lineage_merkle_root(&checksums);
+        assert_eq!(root, lineage_merkle_root(&checksums));
+        assert_ne!(root, lineage_merkle_root(&["b", "a", "c", "d", "e"]));
+        assert_ne!(root, lineage_merkle_root(&checksums[..4]));
+        assert_ne!(lineage_merkle_root(&[]), lineage_merkle_root(&["a"]));
+    }
+
+    #[test]
+    fn merkle_proofs_verify_for_every_entry() {
+        let owned: Vec<String> = (0..7).map(|i| format!("{:064x}", i)).collect();
+        for len in 1..=owned.len() {
+            let checksums: Vec<&str> = owned[..len].iter().map(String::as_str).collect();
+            let root = lineage_merkle_root(&checksums);
+            for index in 0..len {
+                let proof = lineage_merkle_proof(&checksums, index).unwrap();
+                assert_eq!(proof.root, root);
+                assert!(
+                    verify_merkle_proof(&proof, &root),
+                    "len {} index {}",
+                    len,
+                    index
+                );
+
+                let mut forged = proof.clone();
+                forged.checksum = "tampered".to_string();
+                assert!(!verify_merkle_proof(&forged, &root));
+            }
+            assert!(lineage_merkle_proof(&checksums, len).is_none());
+        }
+    }
+}
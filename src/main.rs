@@ -1,23 +1,59 @@
+// `tool_definitions` is one large `json!` literal
+#![recursion_limit = "256"]
+
 #[cfg(feature = "public_stub")]
-mod substrate;
+use iter_mcp_server::substrate;
 
+use iter_mcp_server::{validation, McpError, PingResult, ResponseSanitizer, RpcResponse};
 use serde_json::json;
 use std::io::{BufRead, BufReader, Write};
+use std::time::{Duration, Instant};
 
 fn main() {
-    // Local identity closure: print the actual executable path and CWD at runtime.
-    // (Shows up in some MCP client logs as stderr.)
-    match std::env::current_exe() {
-        Ok(p) => eprintln!("ITER LOCAL PROOF — PATH = {}", p.display()),
-        Err(e) => eprintln!("ITER LOCAL PROOF — PATH = <error: {}>", e),
+    if banner_enabled() {
+        // Local identity closure: print the actual executable path and CWD at runtime.
+        // (Shows up in some MCP client logs as stderr.)
+        match std::env::current_exe() {
+            Ok(p) => eprintln!("ITER LOCAL PROOF — PATH = {}", p.display()),
+            Err(e) => eprintln!("ITER LOCAL PROOF — PATH = <error: {}>", e),
+        }
+        match std::env::current_dir() {
+            Ok(p) => eprintln!("ITER CWD = {}", p.display()),
+            Err(e) => eprintln!("ITER CWD = <error: {}>", e),
+        }
+
+        print_mode_banner();
     }
-    match std::env::current_dir() {
-        Ok(p) => eprintln!("ITER CWD = {}", p.display()),
-        Err(e) => eprintln!("ITER CWD = <error: {}>", e),
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|arg| arg == "--replay") {
+        Some(index) => match args.get(index + 1) {
+            Some(path) => replay_from_file(path),
+            None => {
+                eprintln!("--replay requires a command log path");
+                std::process::exit(2);
+            }
+        },
+        None => run_stdio_server(),
     }
+}
+
+/// Whether startup diagnostics (executable path, CWD, mode banner) go to stderr.
+///
+/// Controlled by `SCG_PRINT_BANNER`; defaults to on in debug builds, off in release.
+fn banner_enabled() -> bool {
+    flag_setting(
+        std::env::var("SCG_PRINT_BANNER").ok().as_deref(),
+        cfg!(debug_assertions),
+    )
+}
 
-    print_mode_banner();
-    run_stdio_server();
+/// Parse a boolean env flag (`1`/`true`/`on`/`yes` or `0`/`false`/`off`/`no`), else `default`
+fn flag_setting(value: Option<&str>, default: bool) -> bool {
+    match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        Some("1" | "true" | "on" | "yes") => true,
+        Some("0" | "false" | "off" | "no") => false,
+        _ => default,
+    }
 }
 
 fn print_mode_banner() {
@@ -30,10 +66,24 @@ fn print_mode_banner() {
 }
 
 fn run_stdio_server() {
-    use crate::substrate::stub::StubRuntime;
     use std::io::BufWriter;
 
-    let mut runtime = StubRuntime::new();
+    let framing = match framing_from_env() {
+        Ok(framing) => framing,
+        Err(err) => {
+            eprintln!("Invalid configuration: {}", err);
+            std::process::exit(2);
+        }
+    };
+    let mut runtime = startup_runtime();
+    let mut session = startup_session(framing);
+    let mut command_log = match command_log_from_env() {
+        Ok(log) => log,
+        Err(err) => {
+            eprintln!("Command log unavailable: {}", err);
+            std::process::exit(2);
+        }
+    };
     let stdin = std::io::stdin();
     let stdout = std::io::stdout();
     let mut reader = BufReader::new(stdin.lock());
@@ -44,74 +94,832 @@ fn run_stdio_server() {
         env!("CARGO_PKG_VERSION")
     );
 
+    let mut pending = PendingRequest::default();
     loop {
-        let mut line = String::new();
-        match reader.read_line(&mut line) {
-            Ok(0) => break, // EOF
-            Ok(_) => {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-
-                // Simple stub handler - parse JSON-RPC and respond
-                match serde_json::from_str::<serde_json::Value>(line) {
-                    Ok(req) => {
-                        let method = req.get("method").and_then(|m| m.as_str()).unwrap_or("");
-                        let id = req.get("id").cloned();
-
-                        // Notifications (no id) get no response per JSON-RPC 2.0 spec
-                        if id.is_none() || id.as_ref().map(|v| v.is_null()).unwrap_or(false) {
-                            // Still call handler for side effects, but don't respond
-                            let _ = handle_stub_request(&mut runtime, method, &req);
-                            continue;
-                        }
-
-                        // Build response as owned bytes - no shared Value, no reuse
-                        let resp = handle_stub_request(&mut runtime, method, &req);
-                        let response_bytes = serde_json::to_vec(&json!({
-                            "jsonrpc": "2.0",
-                            "id": id,
-                            "result": resp
-                        }))
-                        .unwrap_or_default();
-
-                        // Single atomic write + newline + flush (Haltra pattern)
-                        let _ = writer.write_all(&response_bytes);
-                        let _ = writer.write_all(b"\n");
-                        let _ = writer.flush();
-                    }
+        let requests = match framing {
+            Framing::Line => {
+                let mut line = String::new();
+                match reader.read_line(&mut line) {
+                    // EOF: a leftover fragment still gets its parse error
+                    Ok(0) => match pending.finish() {
+                        Some(request) => vec![request],
+                        None => break,
+                    },
+                    Ok(_) => pending.push(&line),
                     Err(e) => {
-                        eprintln!("Failed to parse JSON-RPC request: {}", e);
-                        let error_bytes = serde_json::to_vec(&json!({
-                            "jsonrpc": "2.0",
-                            "id": serde_json::Value::Null,
-                            "error": {
-                                "code": -32700,
-                                "message": "Parse error"
-                            }
-                        }))
-                        .unwrap_or_default();
-                        let _ = writer.write_all(&error_bytes);
-                        let _ = writer.write_all(b"\n");
-                        let _ = writer.flush();
+                        eprintln!("Error reading from stdin: {}", e);
+                        break;
                     }
                 }
             }
-            Err(e) => {
-                eprintln!("Error reading from stdin: {}", e);
-                break;
+            Framing::Binary => match iter_mcp_server::framing::read_frame(&mut reader) {
+                Ok(Some(payload)) => vec![String::from_utf8_lossy(&payload).into_owned()],
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Error reading frame from stdin: {}", e);
+                    break;
+                }
+            },
+        };
+        for request in requests {
+            if let Some(log) = command_log.as_mut() {
+                if let Err(err) = append_command(log, &request) {
+                    eprintln!("Command log write failed: {}", err);
+                }
+            }
+            if let Some(response) = respond_to_line(&mut runtime, &mut session, &request) {
+                match framing {
+                    Framing::Line => write_response(&mut writer, &response),
+                    Framing::Binary => write_framed_response(&mut writer, &response),
+                }
+            }
+        }
+    }
+    if let Err(err) = runtime.flush() {
+        eprintln!("Flush on exit failed: {}", err);
+    }
+}
+
+/// STDIO message framing, chosen by `SCG_MCP_FRAMING`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Framing {
+    /// Newline-delimited JSON (default)
+    #[default]
+    Line,
+    /// 4-byte big-endian length prefix per message (`binary_framing` builds)
+    Binary,
+}
+
+/// Parse `SCG_MCP_FRAMING`: `line` (default) or `binary`.
+///
+/// `binary` in a build without the `binary_framing` feature is an error
+/// rather than a silent fallback, since the client would never get a
+/// readable response.
+fn framing_from_env() -> Result<Framing, McpError> {
+    let raw = std::env::var("SCG_MCP_FRAMING").unwrap_or_default();
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" | "line" => Ok(Framing::Line),
+        "binary" if cfg!(feature = "binary_framing") => Ok(Framing::Binary),
+        "binary" => Err(McpError::BadRequest {
+            message: "SCG_MCP_FRAMING=binary requires a build with the binary_framing feature"
+                .to_string(),
+        }),
+        other => Err(McpError::BadRequest {
+            message: format!("SCG_MCP_FRAMING must be line or binary, got {}", other),
+        }),
+    }
+}
+
+/// Largest fragment held back waiting for the rest of a request; beyond
+/// this the buffer is answered (as a parse error) instead of growing.
+const MAX_PENDING_REQUEST_BYTES: usize = 1 << 20;
+
+/// Reassembles requests whose JSON spans several `read_line` chunks.
+///
+/// Text that is still a valid JSON prefix (an object, array, or string left
+/// open) is held until the rest arrives, so only malformed input is answered
+/// with `-32700`. If a held fragment cannot be continued by the next line,
+/// it is treated as truncated: it is released (to be answered `-32700`)
+/// and that line starts afresh instead of being swallowed.
+#[derive(Debug, Default)]
+struct PendingRequest {
+    buffer: String,
+}
+
+impl PendingRequest {
+    /// Add a chunk; returns the request texts now ready, in order (empty
+    /// while a fragment is still waiting for its continuation)
+    fn push(&mut self, chunk: &str) -> Vec<String> {
+        let had_fragment = !self.buffer.is_empty();
+        self.buffer.push_str(chunk);
+        if self.buffer.trim().is_empty() {
+            self.buffer.clear();
+            return Vec::new();
+        }
+        match json_state(&self.buffer) {
+            JsonState::Incomplete if self.buffer.len() < MAX_PENDING_REQUEST_BYTES => Vec::new(),
+            JsonState::Malformed if had_fragment => {
+                let combined = std::mem::take(&mut self.buffer);
+                let stale = combined[..combined.len() - chunk.len()].to_string();
+                let mut ready = vec![stale];
+                ready.extend(self.push(chunk));
+                ready
+            }
+            _ => vec![std::mem::take(&mut self.buffer)],
+        }
+    }
+
+    /// Whatever is still buffered at end of input
+    fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        (!rest.trim().is_empty()).then_some(rest)
+    }
+}
+
+/// How far a buffered request has got, per `serde_json`'s streaming parser
+#[derive(Debug, PartialEq, Eq)]
+enum JsonState {
+    /// One JSON value followed only by whitespace
+    Complete,
+    /// A valid prefix (or only whitespace) that more input could complete
+    Incomplete,
+    /// Invalid no matter what follows
+    Malformed,
+}
+
+fn json_state(text: &str) -> JsonState {
+    let mut values = serde_json::Deserializer::from_str(text).into_iter::<serde_json::Value>();
+    match values.next() {
+        None => JsonState::Incomplete,
+        Some(Err(err)) if err.is_eof() => JsonState::Incomplete,
+        Some(Err(_)) => JsonState::Malformed,
+        Some(Ok(_)) if text[values.byte_offset()..].trim().is_empty() => JsonState::Complete,
+        Some(Ok(_)) => JsonState::Malformed,
+    }
+}
+
+/// `--replay <file>`: answer a recorded command log and exit.
+///
+/// Runs on a fresh runtime configured from the environment (lineage WAL
+/// recovery and the command log are skipped). With `SCG_DETERMINISM` set,
+/// replaying the same log yields byte-identical output.
+fn replay_from_file(path: &str) {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("Cannot open command log {}: {}", path, err);
+            std::process::exit(2);
+        }
+    };
+    let mut runtime = substrate::stub::StubRuntime::with_config(startup_config());
+    let mut session = startup_session(Framing::Line);
+    let stdout = std::io::stdout();
+    let mut writer = std::io::BufWriter::new(stdout.lock());
+    if let Err(err) = replay_command_log(
+        &mut runtime,
+        &mut session,
+        BufReader::new(file),
+        &mut writer,
+    ) {
+        eprintln!("Replay failed: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn startup_config() -> substrate::config::StubRuntimeConfig {
+    match runtime_config_from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Invalid configuration: {}", err);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn startup_runtime() -> substrate::stub::StubRuntime {
+    let mut runtime = substrate::stub::StubRuntime::with_config(startup_config());
+    if let Err(err) = runtime.recover_lineage() {
+        eprintln!("Lineage WAL recovery failed: {}", err);
+        std::process::exit(2);
+    }
+    runtime
+}
+
+fn startup_session(framing: Framing) -> Session {
+    Session {
+        // Reentrant stdout lock: notifications interleave with responses in order
+        notifications: Some(NotificationSink(Box::new(move |message| {
+            let mut out = std::io::stdout().lock();
+            let bytes = serde_json::to_vec(&message).unwrap_or_default();
+            let _ = match framing {
+                Framing::Line => out
+                    .write_all(&bytes)
+                    .and_then(|()| out.write_all(b"\n"))
+                    .and_then(|()| out.flush()),
+                Framing::Binary => iter_mcp_server::framing::write_frame(&mut out, &bytes),
+            };
+        }))),
+        pretty_responses: flag_setting(
+            std::env::var("SCG_PRETTY_RESPONSES").ok().as_deref(),
+            false,
+        ),
+        recent_ids: match duplicate_id_window_from_env() {
+            Ok(window) => window.map(RecentIds::new),
+            Err(err) => {
+                eprintln!("Invalid configuration: {}", err);
+                std::process::exit(2);
+            }
+        },
+        sanitizer: ResponseSanitizer {
+            redact_forbidden_values: flag_setting(
+                std::env::var("SCG_REDACT_FORBIDDEN_VALUES").ok().as_deref(),
+                false,
+            ),
+            max_array_len: std::env::var("SCG_MAX_RESPONSE_ARRAY_LEN")
+                .ok()
+                .and_then(|raw| raw.trim().parse().ok()),
+        },
+        ..Session::default()
+    }
+}
+
+/// Open `SCG_COMMAND_LOG` for appending, if set.
+///
+/// The log is newline-delimited raw JSON-RPC input as received, one
+/// request per line (blank lines skipped), suitable for `--replay`.
+fn command_log_from_env() -> std::io::Result<Option<std::fs::File>> {
+    match std::env::var("SCG_COMMAND_LOG") {
+        Ok(path) if !path.trim().is_empty() => std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.trim())
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Record one incoming request in the command log (flushed per line).
+///
+/// Requests reassembled from several lines are joined onto one line.
+fn append_command(log: &mut impl Write, line: &str) -> std::io::Result<()> {
+    let line = line.trim().replace(['\r', '\n'], " ");
+    if line.is_empty() {
+        return Ok(());
+    }
+    log.write_all(line.as_bytes())?;
+    log.write_all(b"\n")?;
+    log.flush()
+}
+
+/// Feed every line of a command log through the handler, writing each
+/// response as one line. Returns the number of responses written.
+fn replay_command_log(
+    runtime: &mut substrate::stub::StubRuntime,
+    session: &mut Session,
+    log: impl BufRead,
+    writer: &mut impl Write,
+) -> std::io::Result<usize> {
+    let mut written = 0;
+    for line in log.lines() {
+        if let Some(response) = respond_to_line(runtime, session, &line?) {
+            write_response(writer, &response);
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Answer one raw JSON-RPC line.
+///
+/// Returns `None` for blank lines and notifications (no id), which get no
+/// response per JSON-RPC 2.0.
+fn respond_to_line(
+    runtime: &mut substrate::stub::StubRuntime,
+    session: &mut Session,
+    line: &str,
+) -> Option<RpcResponse> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    // Simple stub handler - parse JSON-RPC and respond
+    match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(req) => {
+            let method = req.get("method").and_then(|m| m.as_str()).unwrap_or("");
+            let id = req.get("id").cloned();
+
+            // Notifications (no id) get no response per JSON-RPC 2.0 spec
+            if id.is_none() || id.as_ref().map(|v| v.is_null()).unwrap_or(false) {
+                // Still call handler for side effects, but don't respond
+                let _ = handle_stub_request(runtime, session, method, &req);
+                return None;
+            }
+
+            let id = id.unwrap_or_default();
+            if session.register_request_id(&id) {
+                // Build response as owned bytes - no shared Value, no reuse
+                let resp = handle_stub_request(runtime, session, method, &req);
+                Some(session.envelope(id, resp))
+            } else {
+                Some(session.error_envelope(id, -32600, "Duplicate request id"))
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to parse JSON-RPC request: {}", e);
+            Some(session.error_envelope(serde_json::Value::Null, -32700, "Parse error"))
+        }
+    }
+}
+
+/// Single atomic write + newline + flush (Haltra pattern)
+fn write_response(writer: &mut impl Write, response: &RpcResponse) {
+    let response_bytes = serde_json::to_vec(response).unwrap_or_default();
+    let _ = writer.write_all(&response_bytes);
+    let _ = writer.write_all(b"\n");
+    let _ = writer.flush();
+}
+
+/// Binary-framing counterpart of [`write_response`]: one length-prefixed frame.
+///
+/// A response over `MAX_FRAME_BYTES` cannot be framed; the client gets an
+/// internal error (-32603) for the same request id instead of silence.
+fn write_framed_response(writer: &mut impl Write, response: &RpcResponse) {
+    let response_bytes = serde_json::to_vec(response).unwrap_or_default();
+    let Err(err) = iter_mcp_server::framing::write_frame(writer, &response_bytes) else {
+        return;
+    };
+    eprintln!("Framed response write failed: {}", err);
+    if response_bytes.len() <= iter_mcp_server::framing::MAX_FRAME_BYTES {
+        return;
+    }
+    let mut error = RpcResponse::error(
+        response.id.clone(),
+        -32603,
+        "Response exceeds the frame size limit",
+    );
+    if response.meta.is_none() {
+        error = error.without_meta();
+    }
+    let error_bytes = serde_json::to_vec(&error).unwrap_or_default();
+    if let Err(err) = iter_mcp_server::framing::write_frame(writer, &error_bytes) {
+        eprintln!("Framed error write failed: {}", err);
+    }
+}
+
+/// Build runtime configuration from the process environment.
+///
+/// - `SCG_CONFIG_BUNDLE`: JSON file from `server.export_config` used as the base; the variables below override it
+/// - `SCG_ADMIN_TOKEN`: enables privileged tools for callers presenting this token
+/// - `SCG_COHERENCE_THRESHOLD`: quarantine threshold in `[0, 1]` (default 0.97)
+/// - `SCG_ID_STRATEGY`: `sequential` (default) or `opaque`
+/// - `SCG_STEP_WORK_BUDGET`: max nodes + edges one propagation step may process
+/// - `SCG_ENABLED_TOOLS`: comma-separated tool allowlist (default: all tools)
+/// - `SCG_LINEAGE_WAL`: JSONL file that mirrors lineage; existing entries are recovered at startup
+/// - `SCG_GOVERNANCE_SAMPLE_INTERVAL_MS`: minimum spacing of request-driven `governor.history` snapshots (default: off)
+/// - `SCG_DEFAULT_BELIEF` / `SCG_DEFAULT_ENERGY`: values for an omitted `node.create` argument (default: required)
+/// - `SCG_MAX_NODE_ENERGY`: largest energy per node (default: 1e12)
+/// - `SCG_MAX_EXPORT_BYTES`: largest `lineage.export` file (default 256 MiB)
+/// - `SCG_EXPORT_DIR`: directory for `lineage.export` / `lineage.diff` files (default: tools disabled)
+/// - `SCG_NODE_HISTORY_DEPTH`: snapshots kept per node for `node.query_at` (default 0: off)
+/// - `SCG_TOOL_TIMEOUTS`: comma-separated `tool=ms` time limits (e.g. `edge.propagate=500`)
+/// - `SCG_DEFAULT_TOOL_TIMEOUT_MS`: time limit for tools not listed in `SCG_TOOL_TIMEOUTS`
+/// - `SCG_ALLOW_TEST_HOOKS`: `1`/`true`/`on`/`yes` exposes the admin test hooks (`admin.force_quarantine`, ...)
+/// - `SCG_STRICT_PARAMS`: `1`/`true`/`on`/`yes` rejects undeclared tool arguments
+/// - `SCG_REQUIRE_INITIALIZE`: `1`/`true`/`on`/`yes` rejects calls made before `initialize`
+/// - `SCG_DETERMINISM`: `1`/`true`/`on`/`yes` omits lineage timestamps and numbers IDs per runtime
+#[cfg(feature = "public_stub")]
+fn runtime_config_from_env() -> Result<substrate::config::StubRuntimeConfig, McpError> {
+    let base = match std::env::var("SCG_CONFIG_BUNDLE") {
+        Ok(path) if !path.trim().is_empty() => load_config_bundle(path.trim())?,
+        _ => substrate::config::StubRuntimeConfig::default(),
+    };
+    let mut config = substrate::config::StubRuntimeConfig {
+        admin_token: std::env::var("SCG_ADMIN_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty()),
+        strict_params: flag_setting(
+            std::env::var("SCG_STRICT_PARAMS").ok().as_deref(),
+            base.strict_params,
+        ),
+        require_initialize: flag_setting(
+            std::env::var("SCG_REQUIRE_INITIALIZE").ok().as_deref(),
+            base.require_initialize,
+        ),
+        allow_test_hooks: flag_setting(
+            std::env::var("SCG_ALLOW_TEST_HOOKS").ok().as_deref(),
+            base.allow_test_hooks,
+        ),
+        deterministic: flag_setting(
+            std::env::var("SCG_DETERMINISM").ok().as_deref(),
+            base.deterministic,
+        ),
+        ..base
+    };
+    if let Ok(raw) = std::env::var("SCG_COHERENCE_THRESHOLD") {
+        config.coherence_threshold = raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_COHERENCE_THRESHOLD is not a number: {}", raw),
+        })?;
+    }
+    if let Ok(raw) = std::env::var("SCG_ID_STRATEGY") {
+        config.id_strategy = match raw.trim() {
+            "sequential" => substrate::config::IdStrategy::Sequential,
+            "opaque" => substrate::config::IdStrategy::Opaque,
+            other => {
+                return Err(McpError::BadRequest {
+                    message: format!("SCG_ID_STRATEGY must be sequential or opaque: {}", other),
+                })
+            }
+        };
+    }
+    if let Ok(raw) = std::env::var("SCG_STEP_WORK_BUDGET") {
+        config.step_work_budget = Some(raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_STEP_WORK_BUDGET is not a count: {}", raw),
+        })?);
+    }
+    if let Ok(raw) = std::env::var("SCG_ENABLED_TOOLS") {
+        config.enabled_tools = Some(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+        );
+    }
+    if let Ok(raw) = std::env::var("SCG_GOVERNANCE_SAMPLE_INTERVAL_MS") {
+        config.governance_sample_interval_ms =
+            Some(raw.trim().parse().map_err(|_| McpError::BadRequest {
+                message: format!("SCG_GOVERNANCE_SAMPLE_INTERVAL_MS is not a count: {}", raw),
+            })?);
+    }
+    if let Ok(raw) = std::env::var("SCG_TOOL_TIMEOUTS") {
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parsed = entry
+                .split_once('=')
+                .and_then(|(tool, ms)| Some((tool.trim(), ms.trim().parse().ok()?)));
+            let Some((tool, ms)) = parsed else {
+                return Err(McpError::BadRequest {
+                    message: format!("SCG_TOOL_TIMEOUTS entry is not tool=ms: {}", entry),
+                });
+            };
+            config
+                .tool_timeouts
+                .insert(tool.to_string(), Duration::from_millis(ms));
+        }
+    }
+    if let Ok(raw) = std::env::var("SCG_DEFAULT_TOOL_TIMEOUT_MS") {
+        let ms = raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_DEFAULT_TOOL_TIMEOUT_MS is not a count: {}", raw),
+        })?;
+        config.default_tool_timeout = Some(Duration::from_millis(ms));
+    }
+    if let Ok(raw) = std::env::var("SCG_DEFAULT_BELIEF") {
+        config.default_belief = Some(raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_DEFAULT_BELIEF is not a number: {}", raw),
+        })?);
+    }
+    if let Ok(raw) = std::env::var("SCG_DEFAULT_ENERGY") {
+        config.default_energy = Some(raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_DEFAULT_ENERGY is not a number: {}", raw),
+        })?);
+    }
+    if let Ok(raw) = std::env::var("SCG_MAX_NODE_ENERGY") {
+        config.max_node_energy = raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_MAX_NODE_ENERGY is not a number: {}", raw),
+        })?;
+    }
+    if let Ok(raw) = std::env::var("SCG_MAX_EXPORT_BYTES") {
+        config.max_export_bytes = raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_MAX_EXPORT_BYTES is not a count: {}", raw),
+        })?;
+    }
+    if let Ok(raw) = std::env::var("SCG_NODE_HISTORY_DEPTH") {
+        config.node_history_depth = raw.trim().parse().map_err(|_| McpError::BadRequest {
+            message: format!("SCG_NODE_HISTORY_DEPTH is not a count: {}", raw),
+        })?;
+    }
+    if let Ok(raw) = std::env::var("SCG_LINEAGE_WAL") {
+        if !raw.trim().is_empty() {
+            config.lineage_wal = Some(raw.trim().into());
+        }
+    }
+    if let Ok(raw) = std::env::var("SCG_EXPORT_DIR") {
+        if !raw.trim().is_empty() {
+            config.export_dir = Some(raw.trim().into());
+        }
+    }
+    config.validate()?;
+    Ok(config)
+}
+
+/// Read a config bundle written from `server.export_config`.
+#[cfg(feature = "public_stub")]
+fn load_config_bundle(path: &str) -> Result<substrate::config::StubRuntimeConfig, McpError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| McpError::BadRequest {
+        message: format!("SCG_CONFIG_BUNDLE {} is not readable: {}", path, e),
+    })?;
+    let bundle = serde_json::from_str(&raw).map_err(|e| McpError::BadRequest {
+        message: format!("SCG_CONFIG_BUNDLE {} is not a config bundle: {}", path, e),
+    })?;
+    substrate::config::StubRuntimeConfig::from_bundle(bundle)
+}
+
+/// Read `SCG_DUPLICATE_ID_WINDOW`: how many recent request ids to remember
+/// for duplicate detection (unset or `0` disables the guard).
+fn duplicate_id_window_from_env() -> Result<Option<usize>, McpError> {
+    let Ok(raw) = std::env::var("SCG_DUPLICATE_ID_WINDOW") else {
+        return Ok(None);
+    };
+    let window: usize = raw.trim().parse().map_err(|_| McpError::BadRequest {
+        message: format!("SCG_DUPLICATE_ID_WINDOW is not a count: {}", raw),
+    })?;
+    Ok((window > 0).then_some(window))
+}
+
+/// Bounded window of recently seen request ids (oldest evicted first).
+#[derive(Debug)]
+struct RecentIds {
+    capacity: usize,
+    order: std::collections::VecDeque<String>,
+    seen: std::collections::HashSet<String>,
+}
+
+impl RecentIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            order: std::collections::VecDeque::new(),
+            seen: std::collections::HashSet::new(),
+        }
+    }
+
+    /// Record `id`; `false` if it is still inside the window
+    fn insert(&mut self, id: &serde_json::Value) -> bool {
+        // Serialized form keeps `1` and `"1"` distinct
+        let key = id.to_string();
+        if self.seen.contains(&key) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
             }
         }
+        self.order.push_back(key.clone());
+        self.seen.insert(key);
+        true
+    }
+}
+
+/// How `tools/call` results are carried in `content[0]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum ContentFormat {
+    /// `{"type": "text", "text": "<json string>"}` (MCP default)
+    #[default]
+    Text,
+    /// `{"type": "json", "json": <value>}` (no double parse)
+    Json,
+}
+
+impl ContentFormat {
+    fn parse(value: &serde_json::Value) -> Result<Self, McpError> {
+        match value.as_str() {
+            Some("text") => Ok(ContentFormat::Text),
+            Some("json") => Ok(ContentFormat::Json),
+            _ => Err(McpError::BadRequest {
+                message: "content_format must be \"text\" or \"json\"".to_string(),
+            }),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ContentFormat::Text => "text",
+            ContentFormat::Json => "json",
+        }
+    }
+}
+
+/// Destination for server-initiated notifications (written between responses).
+struct NotificationSink(Box<dyn FnMut(serde_json::Value)>);
+
+impl std::fmt::Debug for NotificationSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("NotificationSink")
+    }
+}
+
+/// Per-connection state negotiated during `initialize`.
+#[derive(Debug)]
+struct Session {
+    /// Default result format (`capabilities.experimental.content_format`)
+    content_format: ContentFormat,
+    /// Attach `_meta.protocol_version` to responses
+    /// (`capabilities.experimental.response_meta: false` opts out)
+    response_meta: bool,
+    /// Where `notifications/progress` go (`None` drops them)
+    notifications: Option<NotificationSink>,
+    /// Duplicate request-id guard (`None` = off, the default)
+    recent_ids: Option<RecentIds>,
+    /// Pretty-print JSON text results for human debugging
+    /// (`capabilities.experimental.pretty_responses`; compact by default)
+    pretty_responses: bool,
+    /// Set by `initialize` or `notifications/initialized`
+    initialized: bool,
+    /// Send non-fatal notices as `_meta.warnings`
+    /// (`capabilities.experimental.warnings`; off by default)
+    warnings: bool,
+    /// Notices raised while handling the current request
+    pending_warnings: Vec<String>,
+    /// Boundary filter applied to every tool result
+    sanitizer: ResponseSanitizer,
+    /// Write contention when the current request arrived, reported by
+    /// `server.health`. A transport sharing a `SharedStubRuntime` sets it
+    /// from `SharedStubRuntime::load` before dispatch; STDIO answers one
+    /// request at a time, so nothing queues and it stays `ok`.
+    write_load: iter_mcp_server::LoadLevel,
+    /// Whether the transport can hold a `lineage.tail` wait open, reported
+    /// by `server.features`. A transport sharing a `SharedStubRuntime`
+    /// long-polls via `SharedStubRuntime::tail_lineage` and sets it; STDIO
+    /// refuses waits, so it stays `false`.
+    long_poll: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            content_format: ContentFormat::default(),
+            response_meta: true,
+            notifications: None,
+            recent_ids: None,
+            pretty_responses: false,
+            initialized: false,
+            warnings: false,
+            pending_warnings: Vec::new(),
+            sanitizer: ResponseSanitizer::default(),
+            write_load: iter_mcp_server::LoadLevel::Ok,
+            long_poll: false,
+        }
+    }
+}
+
+impl Session {
+    /// Wrap a handler result in a JSON-RPC success envelope, attaching the
+    /// request's warnings for clients that opted in
+    fn envelope(&mut self, id: serde_json::Value, result: serde_json::Value) -> RpcResponse {
+        let response = RpcResponse::success(id, result);
+        let warnings = std::mem::take(&mut self.pending_warnings);
+        if !self.response_meta {
+            response.without_meta()
+        } else if self.warnings {
+            response.with_warnings(warnings)
+        } else {
+            response
+        }
+    }
+
+    /// Wrap an error in a JSON-RPC error envelope
+    fn error_envelope(&self, id: serde_json::Value, code: i32, message: &str) -> RpcResponse {
+        let response = RpcResponse::error(id, code, message);
+        if self.response_meta {
+            response
+        } else {
+            response.without_meta()
+        }
+    }
+
+    /// Track a request id; `false` means it was reused within the guard window
+    fn register_request_id(&mut self, id: &serde_json::Value) -> bool {
+        self.recent_ids
+            .as_mut()
+            .is_none_or(|recent| recent.insert(id))
+    }
+
+    /// Emit `notifications/progress` for requests that carried a progress token
+    fn notify_progress(&mut self, token: Option<&serde_json::Value>, progress: u64, total: u64) {
+        let (Some(token), Some(sink)) = (token, self.notifications.as_mut()) else {
+            return;
+        };
+        (sink.0)(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": { "progressToken": token, "progress": progress, "total": total }
+        }));
+    }
+}
+
+/// Per-request metadata carried in `params._meta`.
+#[derive(Debug, Default)]
+struct RequestMeta {
+    /// Absolute deadline (runtime clock tick) derived from `_meta.deadline_ms` at receipt
+    deadline: Option<u64>,
+    /// Overrides the session's content format for this request
+    content_format: Option<ContentFormat>,
+    /// `_meta.progressToken`: opt in to `notifications/progress` (string or integer)
+    progress_token: Option<serde_json::Value>,
+    /// `_meta.tenant_id`: attributes the call in `admin.sessions`
+    tenant_id: Option<String>,
+    /// Session write load at receipt (not a client field)
+    load: iter_mcp_server::LoadLevel,
+    /// Session transport can long-poll (not a client field)
+    long_poll: bool,
+}
+
+impl RequestMeta {
+    /// Parse `params._meta`; `now` is the runtime clock tick at receipt
+    fn from_params(params: &serde_json::Value, now: u64) -> Result<Self, McpError> {
+        let Some(meta) = params.get("_meta") else {
+            return Ok(Self::default());
+        };
+        let deadline = match meta.get("deadline_ms") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(ms) => {
+                let ms = ms.as_u64().ok_or_else(|| McpError::BadRequest {
+                    message: "_meta.deadline_ms must be a non-negative integer".to_string(),
+                })?;
+                Some(now.saturating_add(ms))
+            }
+        };
+        let content_format = match meta.get("content_format") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(format) => Some(ContentFormat::parse(format)?),
+        };
+        let progress_token = match meta.get("progressToken") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(token @ (serde_json::Value::String(_) | serde_json::Value::Number(_))) => {
+                Some(token.clone())
+            }
+            Some(_) => {
+                return Err(McpError::BadRequest {
+                    message: "_meta.progressToken must be a string or integer".to_string(),
+                })
+            }
+        };
+        let tenant_id = match meta.get("tenant_id") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(tenant)) if !tenant.is_empty() => Some(tenant.clone()),
+            Some(_) => {
+                return Err(McpError::BadRequest {
+                    message: "_meta.tenant_id must be a non-empty string".to_string(),
+                })
+            }
+        };
+        Ok(Self {
+            deadline,
+            content_format,
+            progress_token,
+            tenant_id,
+            load: iter_mcp_server::LoadLevel::Ok,
+            long_poll: false,
+        })
     }
 }
 
+/// Methods answered before the handshake completes, even with `require_initialize`
+const HANDSHAKE_METHODS: &[&str] = &["initialize", "notifications/initialized", "ping"];
+
+/// Dispatch one request and record it in the runtime's per-method metrics.
+///
+/// `tools/call` is keyed by tool name; any response carrying `error` counts as a failure.
+/// `ping` is answered before the runtime is touched (see [`ping_response`]).
 #[cfg(feature = "public_stub")]
 fn handle_stub_request(
     runtime: &mut substrate::stub::StubRuntime,
+    session: &mut Session,
+    method: &str,
+    req: &serde_json::Value,
+) -> serde_json::Value {
+    handle_request_with(|| runtime, session, method, req)
+}
+
+/// [`handle_stub_request`], obtaining the runtime from `acquire` (e.g. a
+/// `SharedStubRuntime` write lock) only once the method needs it, so `ping`
+/// never waits on a lock holder.
+#[cfg(feature = "public_stub")]
+fn handle_request_with<R: std::ops::DerefMut<Target = substrate::stub::StubRuntime>>(
+    acquire: impl FnOnce() -> R,
+    session: &mut Session,
+    method: &str,
+    req: &serde_json::Value,
+) -> serde_json::Value {
+    session.pending_warnings.clear();
+    if method == "ping" {
+        return ping_response();
+    }
+    let mut guard = acquire();
+    let runtime = &mut *guard;
+    let started = Instant::now();
+    runtime.sample_governance_on_request();
+    let response = dispatch_stub_request(runtime, session, method, req);
+    let metric_name = match method {
+        "tools/call" => req
+            .get("params")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or(method),
+        _ => method,
+    };
+    runtime.metrics().record(
+        metric_name,
+        response.get("error").is_none(),
+        started.elapsed(),
+    );
+    response
+}
+
+#[cfg(feature = "public_stub")]
+fn dispatch_stub_request(
+    runtime: &mut substrate::stub::StubRuntime,
+    session: &mut Session,
     method: &str,
     req: &serde_json::Value,
 ) -> serde_json::Value {
+    if runtime.config().require_initialize
+        && !session.initialized
+        && !HANDSHAKE_METHODS.contains(&method)
+    {
+        return json!({"error": {"code": -32002, "message": "Server not initialized"}});
+    }
     match method {
         "initialize" => {
             // Some clients currently advertise protocolVersion "2025-03-26".
@@ -120,18 +928,56 @@ fn handle_stub_request(
                 .get("params")
                 .and_then(|p| p.get("protocolVersion"))
                 .and_then(|v| v.as_str())
-                .unwrap_or("2024-11-05");
+                .unwrap_or(SUPPORTED_MCP_PROTOCOLS[0]);
+
+            let experimental = req
+                .get("params")
+                .and_then(|p| p.get("capabilities"))
+                .and_then(|c| c.get("experimental"));
+            if let Some(enabled) = experimental
+                .and_then(|e| e.get("response_meta"))
+                .and_then(|v| v.as_bool())
+            {
+                session.response_meta = enabled;
+            }
+            if let Some(pretty) = experimental
+                .and_then(|e| e.get("pretty_responses"))
+                .and_then(|v| v.as_bool())
+            {
+                session.pretty_responses = pretty;
+            }
+            if let Some(warnings) = experimental
+                .and_then(|e| e.get("warnings"))
+                .and_then(|v| v.as_bool())
+            {
+                session.warnings = warnings;
+            }
+            if let Some(format) = experimental.and_then(|e| e.get("content_format")) {
+                match ContentFormat::parse(format) {
+                    Ok(format) => session.content_format = format,
+                    Err(err) => return tool_error(err),
+                }
+            }
+            session.initialized = true;
+            runtime.set_negotiated_protocol(client_protocol);
 
             json!({
                 "protocolVersion": client_protocol,
                 "serverInfo": {
-                    "name": "iter-server",
+                    "name": SERVER_NAME,
                     "version": env!("CARGO_PKG_VERSION")
                 },
                 "capabilities": {
                     "tools": {},
                     "resources": {},
-                    "prompts": {}
+                    "prompts": {},
+                    "experimental": {
+                        "mode": "reference-stub",
+                        "content_format": session.content_format.as_str(),
+                        "response_meta": session.response_meta,
+                        "pretty_responses": session.pretty_responses,
+                        "warnings": session.warnings
+                    }
                 }
             })
         }
@@ -139,141 +985,1017 @@ fn handle_stub_request(
             "resources": []
         }),
         "prompts/list" => json!({
-            "prompts": []
-        }),
-        "notifications/initialized" => json!({}),
-        "tools/list" | "tools.list" => json!({
-            "tools": [
-                {
-                    "name": "node.create",
-                    "description": "Create a node",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "belief": { "type": "number", "description": "Initial belief value" },
-                            "energy": { "type": "number", "description": "Initial energy value" }
-                        },
-                        "required": ["belief", "energy"]
-                    }
-                },
-                {
-                    "name": "node.query",
-                    "description": "Query a node",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "node_id": { "type": "string", "description": "Node ID (numeric string)" }
-                        },
-                        "required": ["node_id"]
-                    }
-                },
-                {
-                    "name": "node.mutate",
-                    "description": "Mutate node belief",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "node_id": { "type": "string", "description": "Node ID (numeric string)" },
-                            "delta": { "type": "number", "description": "Belief delta" }
-                        },
-                        "required": ["node_id", "delta"]
-                    }
-                },
-                {
-                    "name": "edge.bind",
-                    "description": "Bind an edge",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "src": { "type": "string", "description": "Source node ID (numeric string)" },
-                            "dst": { "type": "string", "description": "Destination node ID (numeric string)" },
-                            "weight": { "type": "number", "description": "Edge weight" }
-                        },
-                        "required": ["src", "dst", "weight"]
-                    }
-                },
-                {
-                    "name": "edge.propagate",
-                    "description": "Run propagation step",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "edge_id": { "type": "string", "description": "Edge ID (accepted for compatibility, not used)" }
-                        }
-                    }
-                },
-                {
-                    "name": "governor.status",
-                    "description": "Query governor status",
-                    "inputSchema": { "type": "object", "properties": {} }
-                },
-                {
-                    "name": "governance.status",
-                    "description": "Query governance health",
-                    "inputSchema": { "type": "object", "properties": {} }
-                },
-                {
-                    "name": "esv.audit",
-                    "description": "Audit node ESV",
-                    "inputSchema": {
-                        "type": "object",
-                        "properties": {
-                            "node_id": { "type": "string", "description": "Node ID (numeric string)" }
-                        },
-                        "required": ["node_id"]
-                    }
-                },
-                {
-                    "name": "lineage.replay",
-                    "description": "Replay lineage",
-                    "inputSchema": { "type": "object", "properties": {} }
-                }
-            ]
+            "prompts": prompt_definitions()
         }),
+        "prompts/get" => {
+            let name = req
+                .get("params")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or("");
+            match prompt_messages(name) {
+                Some(prompt) => prompt,
+                None => tool_error(McpError::BadRequest {
+                    message: format!("unknown prompt: {}", name),
+                }),
+            }
+        }
+        "notifications/initialized" => {
+            session.initialized = true;
+            json!({})
+        }
+        // Flush durable sinks; the connection stays usable until EOF
+        "shutdown" => match runtime.flush() {
+            Ok(()) => json!({ "flushed": true }),
+            Err(err) => tool_error(err),
+        },
+        "tools/list" | "tools.list" => {
+            let mut tools = tool_definitions();
+            if let Some(tools) = tools.as_array_mut() {
+                tools.retain(|t| {
+                    t.get("name")
+                        .and_then(|n| n.as_str())
+                        .is_some_and(|name| runtime.config().tool_enabled(name))
+                });
+            }
+            json!({ "tools": tools })
+        }
         "tools/call" => {
             let empty_params = json!({});
             let params = req.get("params").unwrap_or(&empty_params);
             let tool_name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            // Disabled tools are unreachable: rejected before any argument handling
+            if !runtime.config().tool_enabled(tool_name) {
+                return json!({"error": {"code": -32601, "message": "Method not found"}});
+            }
             let empty_args = json!({});
             let args = params.get("arguments").unwrap_or(&empty_args);
-            handle_stub_tool(runtime, tool_name, args)
+            let defaulted = with_node_defaults(runtime.config(), tool_name, args);
+            let args = defaulted.as_ref().unwrap_or(args);
+            let now = runtime.clock().tick();
+            let mut meta = match RequestMeta::from_params(params, now) {
+                Ok(meta) => meta,
+                Err(err) => return tool_error(err),
+            };
+            meta.load = session.write_load;
+            meta.long_poll = session.long_poll;
+            // Per-tool timeout tightens (never extends) a client deadline
+            if let Some(limit) = runtime.config().tool_timeout(tool_name) {
+                let limit =
+                    now.saturating_add(u64::try_from(limit.as_millis()).unwrap_or(u64::MAX));
+                meta.deadline = Some(meta.deadline.map_or(limit, |d| d.min(limit)));
+            }
+            if let Some(schema) = tool_input_schema(tool_name) {
+                if let Err(err) = validation::validate_args(args, &schema) {
+                    return tool_error(err);
+                }
+                if runtime.config().strict_params {
+                    if let Err(err) = validation::reject_unknown_args(args, &schema) {
+                        return tool_error(err);
+                    }
+                }
+            }
+            // External policy veto, on sanitized arguments, before any side effect
+            if let Err(err) = runtime.check_policy(tool_name, args) {
+                return tool_error(err);
+            }
+            runtime.set_tenant(meta.tenant_id.as_deref());
+            if MUTATING_TOOLS.contains(&tool_name) {
+                if let Err(err) = runtime.ensure_writable(tool_name) {
+                    return tool_error(err);
+                }
+            }
+            let token = meta.progress_token.as_ref();
+            let mut progress = |done: u64, total: u64| session.notify_progress(token, done, total);
+            let corrections_before = runtime.correction_count();
+            let result = match runtime.take_injected_error(tool_name) {
+                Some(err) => tool_error(err),
+                None => handle_stub_tool(runtime, tool_name, args, &meta, &mut progress),
+            };
+            // Governor rescales are never silent: name them alongside the result
+            let corrections = if runtime.correction_count() > corrections_before {
+                runtime.correction_history().split_off(corrections_before)
+            } else {
+                Vec::new()
+            };
+            for correction in corrections {
+                if correction.rescaled_nodes > 0 {
+                    session.pending_warnings.push(format!(
+                        "governor rescaled the energy of {} nodes after {} ({:?}); see governor.corrections",
+                        correction.rescaled_nodes, tool_name, correction.reason_category
+                    ));
+                }
+            }
+            let result = sanitize_result(&session.sanitizer, result, &mut session.pending_warnings);
+            if let Some(deprecation) = iter_mcp_server::deprecation_for(tool_name) {
+                session.pending_warnings.push(format!(
+                    "{} is deprecated and will be removed in {}: {}",
+                    tool_name, deprecation.removal, deprecation.message
+                ));
+            }
+            let result = apply_content_format(
+                result,
+                meta.content_format.unwrap_or(session.content_format),
+            );
+            if session.pretty_responses {
+                pretty_print_text(result)
+            } else {
+                result
+            }
         }
         _ => json!({"error": "Unknown method"}),
     }
 }
 
-#[cfg(feature = "public_stub")]
-fn handle_stub_tool(
-    runtime: &mut substrate::stub::StubRuntime,
-    tool: &str,
-    args: &serde_json::Value,
-) -> serde_json::Value {
-    match tool {
-        "node.create" => {
-            let belief = args.get("belief").and_then(|b| b.as_f64()).unwrap_or(0.5);
-            let energy = args.get("energy").and_then(|e| e.as_f64()).unwrap_or(100.0);
-            let node = runtime.create_node(belief, energy);
-            json!({"content": [{"type": "text", "text": serde_json::to_string(&node).unwrap()}]})
+/// `ping` result. Needs no runtime access, so it answers even while the
+/// runtime is locked, contended, or quarantined.
+fn ping_response() -> serde_json::Value {
+    json!(PingResult::now())
+}
+
+/// Name of the guided cognitive-physics workflow prompt
+const WALKTHROUGH_PROMPT: &str = "cognitive_physics_walkthrough";
+
+/// Advertised prompts (`prompts/list`).
+fn prompt_definitions() -> serde_json::Value {
+    json!([
+        {
+            "name": WALKTHROUGH_PROMPT,
+            "description": "Guided workflow: create mass, bind a pathway, perturb, propagate, audit",
+            "arguments": []
         }
-        "node.query" => {
-            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
-            let id: u64 = id_str.parse().unwrap_or(0);
-            match runtime.query_node(id) {
-                Some(node) => {
-                    json!({"content": [{"type": "text", "text": serde_json::to_string(&node).unwrap()}]})
-                }
-                None => json!({"error": {"code": 4004, "message": "Node not found"}}),
+    ])
+}
+
+/// Templated message sequence for `prompts/get` (`None` for unknown prompts).
+fn prompt_messages(name: &str) -> Option<serde_json::Value> {
+    if name != WALKTHROUGH_PROMPT {
+        return None;
+    }
+    let steps = [
+        "Create two nodes with `node.create` (e.g. belief 0.6 / energy 1.0 and belief 0.4 / energy 1.0). Note the returned ids.",
+        "Bind a pathway between them with `edge.bind` (src = first id, dst = second id, weight 0.5).",
+        "Perturb the first node with `node.mutate` (delta 0.1) and confirm the new belief with `node.query`.",
+        "Run `edge.propagate` and record the `propagation_checksum` from the artifact.",
+        "Audit the result: call `governance.status` (drift should be within tolerance), `esv.audit` on each node, and `lineage.replay` to confirm every decision replays as `match`.",
+    ];
+    let messages: Vec<serde_json::Value> = steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            json!({
+                "role": "user",
+                "content": { "type": "text", "text": format!("Step {}: {}", i + 1, step) }
+            })
+        })
+        .collect();
+    Some(json!({
+        "description": "Cognitive-physics walkthrough using the Iter tools",
+        "messages": messages
+    }))
+}
+
+/// Run a tool result's JSON text through the boundary sanitizer, collecting
+/// its warnings. Errors and non-JSON text pass through unchanged.
+fn sanitize_result(
+    sanitizer: &ResponseSanitizer,
+    mut result: serde_json::Value,
+    warnings: &mut Vec<String>,
+) -> serde_json::Value {
+    let Some(text) = result.pointer_mut("/content/0/text") else {
+        return result;
+    };
+    let Some(mut value) = text
+        .as_str()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok())
+    else {
+        return result;
+    };
+    let found = sanitizer.sanitize_value(&mut value);
+    if !found.is_empty() {
+        *text = json!(value.to_string());
+        warnings.extend(found);
+    }
+    result
+}
+
+/// Re-encode a text tool result as structured JSON when requested.
+///
+/// Error results and non-JSON text are passed through unchanged.
+fn apply_content_format(mut result: serde_json::Value, format: ContentFormat) -> serde_json::Value {
+    if format == ContentFormat::Text {
+        return result;
+    }
+    if let Some(item) = result.pointer_mut("/content/0") {
+        if item.get("type").and_then(|t| t.as_str()) == Some("text") {
+            let value = match item.get("text") {
+                Some(serde_json::Value::String(text)) => serde_json::from_str(text).ok(),
+                Some(other) => Some(other.clone()),
+                None => None,
+            };
+            if let Some(value) = value {
+                *item = json!({"type": "json", "json": value});
             }
         }
-        "node.mutate" => {
-            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+    }
+    result
+}
+
+/// Re-render JSON text content with indentation (`pretty_responses`).
+///
+/// Handlers emit compact text; non-JSON text is passed through unchanged.
+fn pretty_print_text(mut result: serde_json::Value) -> serde_json::Value {
+    if let Some(serde_json::Value::String(text)) = result.pointer_mut("/content/0/text") {
+        if let Some(pretty) = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        {
+            *text = pretty;
+        }
+    }
+    result
+}
+
+/// Advertised tool definitions (`tools/list`).
+///
+/// Each `inputSchema` is also the contract enforced by `validation::validate_args`
+/// before a `tools/call` reaches the runtime. Tools in the deprecation
+/// registry get a description naming their replacement.
+fn tool_definitions() -> serde_json::Value {
+    let mut tools = json!([
+        {
+            "name": "node.create",
+            "description": "Create a node",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "belief": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Initial belief value" },
+                    "energy": { "type": "number", "minimum": 0.0, "description": "Initial energy value" }
+                },
+                "required": ["belief", "energy"]
+            }
+        },
+        {
+            "name": "node.validate",
+            "description": "Check node.create inputs without creating a node",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "belief": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Initial belief value" },
+                    "energy": { "type": "number", "minimum": 0.0, "description": "Initial energy value" }
+                },
+                "required": ["belief", "energy"]
+            }
+        },
+        {
+            "name": "node.compare",
+            "description": "Compare two nodes (deltas are b - a)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "a": { "type": "string", "pattern": "^[0-9]+$", "description": "First node ID (numeric string)" },
+                    "b": { "type": "string", "pattern": "^[0-9]+$", "description": "Second node ID (numeric string)" }
+                },
+                "required": ["a", "b"]
+            }
+        },
+        {
+            "name": "node.belief_histogram",
+            "description": "Node counts per equal-width belief bucket over [0, 1] (aggregate only)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "buckets": { "type": "integer", "minimum": 1, "maximum": 1000, "description": "Number of buckets" }
+                },
+                "required": ["buckets"]
+            }
+        },
+        {
+            "name": "node.query",
+            "description": "Query a node",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" }
+                },
+                "required": ["node_id"]
+            }
+        },
+        {
+            "name": "node.provenance",
+            "description": "Lineage entries that contributed to a node's current belief, in order",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" }
+                },
+                "required": ["node_id"]
+            }
+        },
+        {
+            "name": "node.query_at",
+            "description": "Node state as of a past lineage tick (requires node_history_depth > 0)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" },
+                    "tick": { "type": "integer", "minimum": 0, "description": "Lineage sequence to look back to" }
+                },
+                "required": ["node_id", "tick"]
+            }
+        },
+        {
+            "name": "node.mutate",
+            "description": "Mutate node belief",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" },
+                    "delta": { "type": "number", "description": "Belief delta" }
+                },
+                "required": ["node_id", "delta"]
+            }
+        },
+        {
+            "name": "node.lock",
+            "description": "Lock a node against mutation and propagation, or unlock it",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" },
+                    "locked": { "type": "boolean", "description": "true to lock (default), false to unlock" }
+                },
+                "required": ["node_id"]
+            }
+        },
+        {
+            "name": "node.patch",
+            "description": "Edit a node's caller metadata with a JSON merge-patch (RFC 7386); belief and energy are not patchable",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" },
+                    "patch": { "type": "object", "description": "Merge-patch applied to the metadata (null deletes a key)" }
+                },
+                "required": ["node_id", "patch"]
+            }
+        },
+        {
+            "name": "node.mutate_where",
+            "description": "Apply a belief delta to every node matching a filter (all or nothing)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "min_belief": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Match belief >= this (inclusive)" },
+                    "max_belief": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Match belief <= this (inclusive)" },
+                    "delta": { "type": "number", "description": "Belief change applied to each match" }
+                },
+                "required": ["delta"]
+            }
+        },
+        {
+            "name": "edge.bind",
+            "description": "Bind an edge",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "src": { "type": "string", "pattern": "^[0-9]+$", "description": "Source node ID (numeric string)" },
+                    "dst": { "type": "string", "pattern": "^[0-9]+$", "description": "Destination node ID (numeric string)" },
+                    "weight": { "type": "number", "description": "Edge weight" }
+                },
+                "required": ["src", "dst", "weight"]
+            }
+        },
+        {
+            "name": "edge.reweight",
+            "description": "Change an existing edge's weight in place",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "edge_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Edge ID (numeric string)" },
+                    "weight": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "New edge weight" }
+                },
+                "required": ["edge_id", "weight"]
+            }
+        },
+        {
+            "name": "edge.estimate_cost",
+            "description": "Project the energy one propagation step along an edge would consume (no side effects)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "edge_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Edge ID (numeric string)" }
+                },
+                "required": ["edge_id"]
+            }
+        },
+        {
+            "name": "edge.pending",
+            "description": "Edge IDs that will move a belief on the next propagation step",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "edge.propagate",
+            "description": "Run propagation step",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "edge_id": { "type": "string", "description": "Edge ID (accepted for compatibility, not used)" },
+                    "steps": { "type": "integer", "minimum": 1, "maximum": 100000, "description": "Number of steps (default 1); honors _meta.deadline_ms" }
+                }
+            }
+        },
+        {
+            "name": "governor.status",
+            "description": "Query governor status",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governance.status",
+            "description": "Query governance health",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "esv.audit",
+            "description": "Audit node ESV",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_id": { "type": "string", "pattern": "^[0-9]+$", "description": "Node ID (numeric string)" }
+                },
+                "required": ["node_id"]
+            }
+        },
+        {
+            "name": "lineage.replay",
+            "description": "Replay lineage",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "operation": { "type": "string", "description": "Only entries with this category or tool label (e.g. \"node.create\")" },
+                    "limit": { "type": "integer", "minimum": 0, "description": "Maximum entries to return" },
+                    "offset": { "type": "integer", "minimum": 0, "description": "Matching entries to skip" }
+                }
+            }
+        },
+        {
+            "name": "substrate.transaction",
+            "description": "Run node reads/mutations atomically (all-or-nothing)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "ops": { "type": "array", "description": "Ops: {op: query|mutate|require, node_id, delta?, min_belief?, max_belief?}" }
+                },
+                "required": ["ops"]
+            }
+        },
+        {
+            "name": "graph.append_chain",
+            "description": "Create a node and, if from is given, bind from → new node in one atomic step",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "belief": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Initial belief value" },
+                    "energy": { "type": "number", "minimum": 0.0, "description": "Initial energy value" },
+                    "from": { "type": "string", "pattern": "^[0-9]+$", "description": "Previous node in the chain (numeric string)" },
+                    "weight": { "type": "number", "minimum": 0.0, "maximum": 1.0, "description": "Edge weight (default 0.5)" }
+                },
+                "required": ["belief", "energy"]
+            }
+        },
+        {
+            "name": "graph.degree_stats",
+            "description": "Aggregate in/out degree statistics (no adjacency)",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "energy.attribution",
+            "description": "Cumulative energy consumed by mutations, propagation, and other operations",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "energy.conservation_proof",
+            "description": "Energy balance (before, after, dissipated, balanced) of the latest propagation step",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "verify.derived_state",
+            "description": "Check a client-computed derived state against the server's derivation",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "node_count": { "type": "integer", "minimum": 0, "description": "Number of nodes" },
+                    "edge_count": { "type": "integer", "minimum": 0, "description": "Number of edges" },
+                    "total_energy": { "type": "number", "description": "Sum of node energies" },
+                    "mean_belief": { "type": "number", "description": "Mean node belief" }
+                },
+                "required": ["node_count", "edge_count", "total_energy", "mean_belief"]
+            }
+        },
+        {
+            "name": "graph.orphans",
+            "description": "IDs of nodes with no incident edges (reveals only whether each node has degree zero)",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "graph.import",
+            "description": "Recreate a whole graph in one all-or-nothing call; returns the old-to-new node ID mapping",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "nodes": { "type": "array", "description": "[{ id, belief, energy }]" },
+                    "edges": { "type": "array", "description": "[{ src, dst, weight }] referencing node ids" }
+                },
+                "required": ["nodes"]
+            }
+        },
+        {
+            "name": "graph.export",
+            "description": "Render the node/edge structure as DOT, GraphML, or importable JSON (privileged: exposes topology)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "format": { "type": "string", "description": "\"dot\", \"graphml\", or \"json\" (graph.import input)" },
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["format", "admin_token"]
+            }
+        },
+        {
+            "name": "graph.reachable",
+            "description": "Whether a directed path leads from src to dst (privileged: reveals connectivity)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "src": { "type": "string", "pattern": "^[0-9]+$", "description": "Source node ID (numeric string)" },
+                    "dst": { "type": "string", "pattern": "^[0-9]+$", "description": "Destination node ID (numeric string)" },
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["src", "dst", "admin_token"]
+            }
+        },
+        {
+            "name": "server.health",
+            "description": "Health summary with a coarse write-load indicator (ok | elevated | saturated)",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.features",
+            "description": "Behavioral capability flags enabled in this build and configuration",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.operation_count",
+            "description": "Operations recorded since startup (activity signal, excludes recovered lineage)",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.fingerprint",
+            "description": "SHA-256 of the whole observable state (nodes, edges, lineage root, config) for exact-state checks",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.negotiated_version",
+            "description": "MCP protocol version agreed during initialize, and the range this server supports",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.version",
+            "description": "Server name, server version, protocol version, and build id",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.deprecations",
+            "description": "Deprecation schedule: deprecated items, removal versions, and replacements",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "admin.force_quarantine",
+            "description": "Enter quarantine with a synthetic reason (test hook: requires allow_test_hooks; privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["admin_token"]
+            }
+        },
+        {
+            "name": "admin.inject_error",
+            "description": "Make the next call to a tool fail with the given error code (test hook: requires allow_test_hooks; privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" },
+                    "method": { "type": "string", "description": "Tool whose next call fails" },
+                    "error_code": { "type": "integer", "minimum": 0, "description": "McpError code to return (e.g. 2000)" }
+                },
+                "required": ["admin_token", "method", "error_code"]
+            }
+        },
+        {
+            "name": "admin.clear_quarantine",
+            "description": "Leave quarantine (test hook: requires allow_test_hooks; privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["admin_token"]
+            }
+        },
+        {
+            "name": "admin.sessions",
+            "description": "Per-tenant node/edge counts and last activity, tenant IDs hashed (privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["admin_token"]
+            }
+        },
+        {
+            "name": "server.config",
+            "description": "Sanitized view of the active runtime configuration",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.export_config",
+            "description": "Portable config bundle (no secrets) for SCG_CONFIG_BUNDLE",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "server.metrics",
+            "description": "Per-method call, error, and latency counters",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "telemetry.thresholds",
+            "description": "Drift, coherence, and ESV limits currently in effect",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.corrections",
+            "description": "List governor correction cycles",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.peak_drift",
+            "description": "Largest absolute energy drift observed since startup or the last reset",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.reset_peak",
+            "description": "Clear the recorded peak drift",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.evaluate",
+            "description": "What-if governance status under other thresholds, against the current state (changes nothing)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "drift_tolerance": { "type": "number", "minimum": 0, "description": "Candidate drift tolerance (default: active value)" },
+                    "coherence_threshold": { "type": "number", "minimum": 0, "maximum": 1, "description": "Candidate quarantine threshold (default: active value)" }
+                }
+            }
+        },
+        {
+            "name": "governor.stalls",
+            "description": "Edge bind and propagation step stall counts since startup or the last reset",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.reset_stalls",
+            "description": "Clear the stall counters",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "governor.history",
+            "description": "Recent periodic governance snapshots, oldest first",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": { "type": "integer", "minimum": 1, "description": "Maximum snapshots to return (default 32)" }
+                }
+            }
+        },
+        {
+            "name": "governor.rebaseline",
+            "description": "Reset the energy drift baseline (privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" }
+                },
+                "required": ["admin_token"]
+            }
+        },
+        {
+            "name": "lineage.replay_episode",
+            "description": "Replay a recorded episode against a fresh runtime and report determinism",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "steps": { "type": "array", "description": "Recorded steps: {op, ...args, expected_checksum?}" }
+                },
+                "required": ["steps"]
+            }
+        },
+        {
+            "name": "lineage.shards",
+            "description": "List lineage shard boundaries",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "lineage.attest",
+            "description": "Check a held lineage head checksum against the current chain",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "expected_checksum": { "type": "string", "description": "Head checksum from an earlier export or attestation" }
+                },
+                "required": ["expected_checksum"]
+            }
+        },
+        {
+            "name": "lineage.merkle_root",
+            "description": "Merkle root over lineage entry checksums",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "lineage.merkle_proof",
+            "description": "Inclusion proof of one lineage entry against the Merkle root",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "sequence": { "type": "integer", "minimum": 0, "description": "Lineage sequence to prove" }
+                },
+                "required": ["sequence"]
+            }
+        },
+        {
+            "name": "lineage.tail",
+            "description": "Lineage entries after a cursor (answers immediately; STDIO cannot long-poll)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "since": { "type": "integer", "minimum": 0, "description": "Last sequence already seen (omit to start from the beginning)" }
+                }
+            }
+        },
+        {
+            "name": "lineage.export",
+            "description": "Write the lineage log to a new file in the export directory as a JSON array (optionally gzip-compressed; privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" },
+                    "path": { "type": "string", "description": "Output path relative to the export directory (must not exist)" },
+                    "compress": { "type": "boolean", "description": "Gzip the output (lineage_gzip builds only)" }
+                },
+                "required": ["admin_token", "path"]
+            }
+        },
+        {
+            "name": "lineage.diff",
+            "description": "Compare two lineage export files in the export directory and report the first divergence (privileged)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "admin_token": { "type": "string", "description": "Admin token" },
+                    "a": { "type": "string", "description": "Baseline export (relative to the export directory)" },
+                    "b": { "type": "string", "description": "Export to compare (relative to the export directory)" }
+                },
+                "required": ["admin_token", "a", "b"]
+            }
+        },
+        {
+            "name": "lineage.get",
+            "description": "Fetch a single lineage entry",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "sequence": { "type": "integer", "description": "Lineage sequence number" }
+                },
+                "required": ["sequence"]
+            }
+        }
+    ]);
+    for tool in tools.as_array_mut().into_iter().flatten() {
+        let name = tool["name"].as_str().unwrap_or_default();
+        let Some(entry) = iter_mcp_server::DEPRECATIONS
+            .iter()
+            .find(|e| e.item == name)
+        else {
+            continue;
+        };
+        let description = tool["description"].as_str().unwrap_or_default();
+        tool["description"] = json!(format!(
+            "Deprecated alias of {} (removed in {}); use it instead. {}",
+            entry.replacement, entry.removed_in, description
+        ));
+    }
+    tools
+}
+
+/// Tools that change substrate state; refused while the runtime is quarantined.
+const MUTATING_TOOLS: &[&str] = &[
+    "node.create",
+    "node.mutate",
+    "node.lock",
+    "node.patch",
+    "node.mutate_where",
+    "edge.bind",
+    "edge.reweight",
+    "edge.propagate",
+    "graph.append_chain",
+    "graph.import",
+    "substrate.transaction",
+];
+
+/// Server name reported by `initialize` and `server.version`
+const SERVER_NAME: &str = "iter-server";
+
+/// Oldest and newest MCP protocol revisions this server is known to work
+/// with. `initialize` echoes whatever the client asks for; the range is
+/// reported (`server.negotiated_version`) so a client can spot a mismatch.
+const SUPPORTED_MCP_PROTOCOLS: [&str; 2] = ["2024-11-05", "2025-03-26"];
+
+/// `server.version`: server and protocol versions in one answer.
+///
+/// `build` is `ITER_BUILD_ID` at compile time (e.g. a git SHA), if set.
+fn server_version() -> serde_json::Value {
+    json!({
+        "server_name": SERVER_NAME,
+        "server_version": env!("CARGO_PKG_VERSION"),
+        "protocol_version": iter_mcp_server::PROTOCOL_VERSION,
+        "build": option_env!("ITER_BUILD_ID"),
+    })
+}
+
+/// Capability flags for `server.features`, sorted.
+///
+/// Unlike `tools/list` these name behaviors, and a flag is only reported
+/// when this build, configuration and transport (`long_poll`) actually
+/// provide it.
+fn server_features(runtime: &substrate::stub::StubRuntime, long_poll: bool) -> Vec<&'static str> {
+    let config = runtime.config();
+    let mut features = vec![
+        "content_format_json",
+        "deadlines",
+        "pretty_responses",
+        "progress_notifications",
+    ];
+    let optional = [
+        ("binary_framing", cfg!(feature = "binary_framing")),
+        ("lineage_gzip", cfg!(feature = "lineage_gzip")),
+        (
+            "lineage_tail",
+            long_poll && config.tool_enabled("lineage.tail"),
+        ),
+        ("lineage_wal", config.lineage_wal.is_some()),
+        (
+            "node_history",
+            config.node_history_depth > 0 && config.tool_enabled("node.query_at"),
+        ),
+        ("strict_params", config.strict_params),
+        ("test_hooks", config.allow_test_hooks),
+        ("transactions", config.tool_enabled("substrate.transaction")),
+    ];
+    features.extend(
+        optional
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name),
+    );
+    features.sort_unstable();
+    features
+}
+
+/// Look up the advertised input schema for a tool.
+fn tool_input_schema(tool: &str) -> Option<serde_json::Value> {
+    tool_definitions()
+        .as_array()?
+        .iter()
+        .find(|t| t.get("name").and_then(|n| n.as_str()) == Some(tool))
+        .and_then(|t| t.get("inputSchema").cloned())
+}
+
+#[cfg(feature = "public_stub")]
+fn handle_stub_tool(
+    runtime: &mut substrate::stub::StubRuntime,
+    tool: &str,
+    args: &serde_json::Value,
+    meta: &RequestMeta,
+    progress: &mut dyn FnMut(u64, u64),
+) -> serde_json::Value {
+    match tool {
+        "node.create" => {
+            let (belief, energy) = match node_inputs(runtime.config(), args) {
+                Ok(inputs) => inputs,
+                Err(err) => return tool_error(err),
+            };
+            if let Err(err) = runtime.validate_node_inputs(belief, energy) {
+                return tool_error(err);
+            }
+            let node = runtime.create_node(belief, energy);
+            tool_text(&node)
+        }
+        "node.validate" => {
+            let (belief, energy) = match node_inputs(runtime.config(), args) {
+                Ok(inputs) => inputs,
+                Err(err) => return tool_error(err),
+            };
+            match runtime.validate_node_inputs(belief, energy) {
+                Ok(()) => tool_text(&json!({"valid": true})),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.compare" => {
+            let id = |key: &str| -> u64 {
+                args.get(key)
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0)
+            };
+            match runtime.compare_nodes(id("a"), id("b")) {
+                Ok(comparison) => tool_text(&comparison),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.belief_histogram" => {
+            let buckets = args.get("buckets").and_then(|b| b.as_u64()).unwrap_or(0);
+            match runtime.belief_histogram(buckets as usize) {
+                Ok(histogram) => tool_text(&histogram),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.provenance" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+            let id: u64 = id_str.parse().unwrap_or(0);
+            match runtime.belief_provenance(id) {
+                Ok(steps) => tool_text(&steps),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.query_at" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+            let id: u64 = id_str.parse().unwrap_or(0);
+            let tick = args.get("tick").and_then(|t| t.as_u64()).unwrap_or(0);
+            match runtime.query_node_at(id, tick) {
+                Ok(snapshot) => tool_text(&snapshot),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.query" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+            let id: u64 = id_str.parse().unwrap_or(0);
+            match runtime.query_node(id) {
+                Some(node) => tool_text(&node),
+                None => json!({"error": {"code": 4004, "message": "Node not found"}}),
+            }
+        }
+        "node.mutate" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
             let id: u64 = id_str.parse().unwrap_or(0);
             let delta = args.get("delta").and_then(|d| d.as_f64()).unwrap_or(0.0);
-            match runtime.mutate_node(id, delta) {
-                Some(node) => {
-                    json!({"content": [{"type": "text", "text": serde_json::to_string(&node).unwrap()}]})
+            match runtime.try_mutate_node(id, delta) {
+                Ok(node) => tool_text(&node),
+                Err(McpError::NodeNotFound { .. }) => {
+                    json!({"error": {"code": 4004, "message": "Node not found"}})
                 }
-                None => json!({"error": {"code": 4004, "message": "Node not found"}}),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.lock" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+            let id: u64 = id_str.parse().unwrap_or(0);
+            let locked = args.get("locked").and_then(|l| l.as_bool()).unwrap_or(true);
+            match runtime.lock_node(id, locked) {
+                Ok(state) => tool_text(&state),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.patch" => {
+            let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
+            let id: u64 = id_str.parse().unwrap_or(0);
+            let patch = args.get("patch").cloned().unwrap_or_else(|| json!({}));
+            match runtime.patch_node_metadata(id, &patch) {
+                Ok(metadata) => tool_text(&json!({"node_id": id_str, "metadata": metadata})),
+                Err(err) => tool_error(err),
+            }
+        }
+        "node.mutate_where" => {
+            let filter = substrate::stub::NodeFilter {
+                min_belief: args.get("min_belief").and_then(|b| b.as_f64()),
+                max_belief: args.get("max_belief").and_then(|b| b.as_f64()),
+            };
+            let delta = args.get("delta").and_then(|d| d.as_f64()).unwrap_or(0.0);
+            match runtime.mutate_where(filter, delta) {
+                Ok(report) => tool_text(&report),
+                Err(err) => tool_error(err),
             }
         }
         "edge.bind" => {
@@ -288,35 +2010,1854 @@ fn handle_stub_tool(
                 .and_then(|d| d.parse().ok())
                 .unwrap_or(0);
             let weight = args.get("weight").and_then(|w| w.as_f64()).unwrap_or(0.5);
-            match runtime.bind_edge(src, dst, weight) {
-                Some(edge) => {
-                    json!({"content": [{"type": "text", "text": serde_json::to_string(&edge).unwrap()}]})
-                }
-                None => json!({"error": {"code": 4004, "message": "Node not found"}}),
+            match runtime.try_bind_edge(src, dst, weight) {
+                Ok(edge) => tool_text(&edge),
+                Err(err) => tool_error(err),
+            }
+        }
+        "edge.estimate_cost" => {
+            let edge_id: u64 = args
+                .get("edge_id")
+                .and_then(|e| e.as_str())
+                .and_then(|e| e.parse().ok())
+                .unwrap_or(0);
+            match runtime.estimate_propagation_cost(edge_id) {
+                Ok(cost) => tool_text(&json!({"edge_id": edge_id, "estimated_energy_cost": cost})),
+                Err(err) => tool_error(err),
+            }
+        }
+        "edge.pending" => tool_text(&json!({"edge_ids": runtime.pending_propagations()})),
+        "edge.reweight" => {
+            let edge_id: u64 = args
+                .get("edge_id")
+                .and_then(|e| e.as_str())
+                .and_then(|e| e.parse().ok())
+                .unwrap_or(0);
+            let weight = args.get("weight").and_then(|w| w.as_f64()).unwrap_or(0.5);
+            match runtime.reweight_edge(edge_id, weight) {
+                Ok(edge) => tool_text(&edge),
+                Err(err) => tool_error(err),
             }
         }
         "edge.propagate" => {
-            let msg = runtime.propagate();
-            json!({"content": [{"type": "text", "text": msg}]})
+            let steps = args.get("steps").and_then(|s| s.as_u64()).unwrap_or(1);
+            let reported = runtime
+                .propagate_steps_with_progress(steps, meta.deadline, |done| progress(done, steps));
+            match reported {
+                Ok(msg) => json!({"content": [{"type": "text", "text": msg}]}),
+                Err(err) => tool_error(err),
+            }
         }
         "governor.status" | "governance.status" => {
+            if let Err(err) = runtime.checked_total_energy() {
+                return tool_error(err);
+            }
+            runtime.observe_drift();
             let status = runtime.governor_status();
-            json!({"content": [{"type": "text", "text": serde_json::to_string(&status).unwrap()}]})
+            tool_text(&status)
         }
         "esv.audit" => {
             let id_str = args.get("node_id").and_then(|i| i.as_str()).unwrap_or("0");
             let id: u64 = id_str.parse().unwrap_or(0);
             match runtime.esv_audit(id) {
-                Some(audit) => {
-                    json!({"content": [{"type": "text", "text": serde_json::to_string(&audit).unwrap()}]})
-                }
+                Some(audit) => tool_text(&audit),
                 None => json!({"error": {"code": 4004, "message": "Node not found"}}),
             }
         }
         "lineage.replay" => {
-            let lineage = runtime.lineage_replay();
-            json!({"content": [{"type": "text", "text": serde_json::to_string(&lineage).unwrap()}]})
+            let operation = args.get("operation").and_then(|o| o.as_str());
+            let limit = args
+                .get("limit")
+                .and_then(|l| l.as_u64())
+                .map(|l| usize::try_from(l).unwrap_or(usize::MAX));
+            let offset = args
+                .get("offset")
+                .and_then(|o| o.as_u64())
+                .map_or(0, |o| usize::try_from(o).unwrap_or(usize::MAX));
+            let lineage = runtime.lineage_replay_filtered(operation, limit, offset);
+            tool_text(&lineage)
+        }
+        "substrate.transaction" => {
+            let ops = match serde_json::from_value(args["ops"].clone()) {
+                Ok(ops) => ops,
+                Err(e) => {
+                    return tool_error(McpError::BadRequest {
+                        message: format!("invalid ops: {}", e),
+                    })
+                }
+            };
+            match runtime.transaction(ops) {
+                Ok(states) => tool_text(&states),
+                Err(err) => tool_error(err),
+            }
+        }
+        "graph.append_chain" => {
+            let (belief, energy) = match node_inputs(runtime.config(), args) {
+                Ok(inputs) => inputs,
+                Err(err) => return tool_error(err),
+            };
+            if let Err(err) = runtime.validate_node_inputs(belief, energy) {
+                return tool_error(err);
+            }
+            let weight = args.get("weight").and_then(|w| w.as_f64()).unwrap_or(0.5);
+            let from = args
+                .get("from")
+                .and_then(|f| f.as_str())
+                .map(|f| (f.parse().unwrap_or(0), weight));
+            match runtime.append_chain(belief, energy, from) {
+                Ok(link) => tool_text(&link),
+                Err(err) => tool_error(err),
+            }
+        }
+        "graph.degree_stats" => tool_text(&runtime.degree_stats()),
+        "energy.attribution" => tool_text(&runtime.energy_attribution()),
+        "energy.conservation_proof" => match runtime.latest_conservation_proof() {
+            Some(proof) => tool_text(&proof),
+            None => tool_error(McpError::BadRequest {
+                message: "no propagation step has been recorded".to_string(),
+            }),
+        },
+        "verify.derived_state" => match serde_json::from_value(args.clone()) {
+            Ok(claimed) => tool_text(&runtime.verify_derived_state(&claimed)),
+            Err(e) => tool_error(McpError::BadRequest {
+                message: format!("invalid derived state: {}", e),
+            }),
+        },
+        "graph.orphans" => tool_text(&json!({"node_ids": runtime.orphan_nodes()})),
+        "admin.inject_error" => {
+            // Flag before token, so a disabled hook never checks a token
+            if let Err(err) = runtime.ensure_test_hooks(tool) {
+                return tool_error(err);
+            }
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin(tool, token) {
+                return tool_error(err);
+            }
+            let method = args.get("method").and_then(|m| m.as_str()).unwrap_or("");
+            if tool_input_schema(method).is_none() {
+                return tool_error(McpError::BadRequest {
+                    message: "method is not a known tool".to_string(),
+                });
+            }
+            let code = args.get("error_code").and_then(|c| c.as_u64()).unwrap_or(0);
+            let code = u32::try_from(code).unwrap_or(u32::MAX);
+            match runtime.inject_error(method, code) {
+                Ok(armed) => tool_text(&json!({
+                    "method": method,
+                    "error_code": armed.code(),
+                    "code_string": armed.code_string(),
+                })),
+                Err(err) => tool_error(err),
+            }
+        }
+        "admin.force_quarantine" | "admin.clear_quarantine" => {
+            if let Err(err) = runtime.ensure_test_hooks(tool) {
+                return tool_error(err);
+            }
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin(tool, token) {
+                return tool_error(err);
+            }
+            let applied = if tool == "admin.force_quarantine" {
+                runtime.force_quarantine()
+            } else {
+                runtime.clear_quarantine()
+            };
+            match applied {
+                Ok(()) => tool_text(&runtime.governor_status()),
+                Err(err) => tool_error(err),
+            }
+        }
+        "admin.sessions" => {
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin("admin.sessions", token) {
+                return tool_error(err);
+            }
+            tool_text(&runtime.session_summaries())
+        }
+        "graph.export" => {
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin("graph.export", token) {
+                return tool_error(err);
+            }
+            let format = match serde_json::from_value(args["format"].clone()) {
+                Ok(format) => format,
+                Err(_) => {
+                    return tool_error(McpError::BadRequest {
+                        message: "format must be \"dot\", \"graphml\", or \"json\"".to_string(),
+                    })
+                }
+            };
+            json!({"content": [{"type": "text", "text": runtime.export_graph(format)}]})
+        }
+        "graph.import" => {
+            let graph = match serde_json::from_value(args.clone()) {
+                Ok(graph) => graph,
+                Err(e) => {
+                    return tool_error(McpError::BadRequest {
+                        message: format!("invalid graph: {}", e),
+                    })
+                }
+            };
+            match runtime.import_graph(graph) {
+                Ok(report) => tool_text(&report),
+                Err(err) => tool_error(err),
+            }
+        }
+        "graph.reachable" => {
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin("graph.reachable", token) {
+                return tool_error(err);
+            }
+            let src: u64 = args
+                .get("src")
+                .and_then(|s| s.as_str())
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            let dst: u64 = args
+                .get("dst")
+                .and_then(|d| d.as_str())
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(0);
+            match runtime.is_reachable(src, dst) {
+                Ok(reachable) => tool_text(&json!({ "reachable": reachable })),
+                Err(err) => tool_error(err),
+            }
+        }
+        "server.config" => tool_text(&runtime.config_summary()),
+        "server.export_config" => tool_text(&runtime.export_config()),
+        "server.metrics" => tool_text(&runtime.metrics().summary()),
+        "server.health" => {
+            let status = runtime.governor_status();
+            tool_text(&json!({
+                "healthy": status.healthy,
+                "quarantined": status.quarantined,
+                "load": meta.load,
+            }))
+        }
+        "server.features" => {
+            tool_text(&json!({ "features": server_features(runtime, meta.long_poll) }))
+        }
+        "server.version" => tool_text(&server_version()),
+        "server.negotiated_version" => tool_text(&json!({
+            "negotiated": runtime.negotiated_protocol(),
+            "server_supported": SUPPORTED_MCP_PROTOCOLS,
+        })),
+        "server.operation_count" => tool_text(&json!({
+            "total_operations": runtime.operation_count(),
+        })),
+        "server.fingerprint" => tool_text(&json!({"fingerprint": runtime.fingerprint()})),
+        "server.deprecations" => tool_text(&iter_mcp_server::DEPRECATIONS),
+        "telemetry.thresholds" => tool_text(&runtime.thresholds()),
+        "governor.corrections" => tool_text(&runtime.correction_history()),
+        "governor.peak_drift" => tool_text(&runtime.peak_drift()),
+        "governor.reset_peak" => {
+            runtime.reset_peak_drift();
+            tool_text(&runtime.peak_drift())
+        }
+        "governor.evaluate" => {
+            let threshold = |name: &str| args.get(name).and_then(|v| v.as_f64());
+            match runtime.evaluate_governance(
+                threshold("drift_tolerance"),
+                threshold("coherence_threshold"),
+            ) {
+                Ok(status) => tool_text(&status),
+                Err(err) => tool_error(err),
+            }
+        }
+        "governor.stalls" => tool_text(&runtime.stall_counters()),
+        "governor.reset_stalls" => {
+            runtime.reset_stall_counters();
+            tool_text(&runtime.stall_counters())
+        }
+        "governor.history" => {
+            let limit = args.get("limit").and_then(|l| l.as_u64()).unwrap_or(32);
+            tool_text(&runtime.governance_history(limit as usize))
+        }
+        "governor.rebaseline" => {
+            let token = args.get("admin_token").and_then(|t| t.as_str());
+            if let Err(err) = runtime.authorize_admin("governor.rebaseline", token) {
+                return tool_error(err);
+            }
+            match runtime.rebaseline_energy() {
+                Ok(()) => tool_text(&runtime.governor_status()),
+                Err(err) => tool_error(err),
+            }
+        }
+        "lineage.replay_episode" => {
+            let episode = match serde_json::from_value(args.clone()) {
+                Ok(episode) => episode,
+                Err(e) => {
+                    return tool_error(McpError::BadRequest {
+                        message: format!("invalid episode: {}", e),
+                    })
+                }
+            };
+            match runtime.replay_episode(episode) {
+                Ok(report) => tool_text(&report),
+                Err(err) => tool_error(err),
+            }
+        }
+        "lineage.shards" => tool_text(&runtime.lineage_shards()),
+        "lineage.attest" => {
+            let expected = args
+                .get("expected_checksum")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default();
+            tool_text(&runtime.lineage_attest(expected))
+        }
+        "lineage.merkle_root" => tool_text(&json!({
+            "root": runtime.lineage_merkle_root(),
+            "entries": runtime.lineage_entries().len(),
+        })),
+        "lineage.merkle_proof" => {
+            let sequence = args.get("sequence").and_then(|s| s.as_u64()).unwrap_or(0);
+            match runtime.lineage_merkle_proof(sequence) {
+                Ok(proof) => tool_text(&proof),
+                Err(err) => tool_error(err),
+            }
+        }
+        "lineage.tail" => {
+            // STDIO serializes requests, so nothing can append while this call
+            // waits: answer immediately, and refuse a wait rather than ignore
+            // it. Shared transports long-poll via `SharedStubRuntime::tail_lineage`.
+            let timeout_ms = args.get("timeout_ms").and_then(|t| t.as_u64());
+            if timeout_ms.is_some_and(|ms| ms > 0) {
+                return tool_error(McpError::BadRequest {
+                    message: "timeout_ms is not supported over STDIO; poll with since".to_string(),
+                });
+            }
+            let since = args.get("since").and_then(|s| s.as_u64());
+            tool_text(&runtime.lineage_since(since))
+        }
+        "lineage.export" => {
+            let dir = match export_dir(runtime, tool, args) {
+                Ok(dir) => dir,
+                Err(err) => return tool_error(err),
+            };
+            let params: iter_mcp_server::types::ExportLineageParams =
+                match serde_json::from_value(args.clone()) {
+                    Ok(params) => params,
+                    Err(_) => {
+                        return tool_error(McpError::BadRequest {
+                            message: "path is required and must be a string".to_string(),
+                        })
+                    }
+                };
+            let path = match validation::validate_relative_path(&params.path)
+                .and_then(|path| iter_mcp_server::lineage::resolve_export_path(&dir, path))
+            {
+                Ok(path) => path,
+                Err(err) => return tool_error(err),
+            };
+            let entries = runtime.lineage_filtered(None, None, 0);
+            match iter_mcp_server::lineage::write_lineage_export(
+                &entries,
+                &path,
+                params.compress.unwrap_or(false),
+                runtime.config().max_export_bytes,
+            ) {
+                Ok(report) => tool_text(&report),
+                Err(err) => tool_error(err),
+            }
+        }
+        "lineage.diff" => {
+            let dir = match export_dir(runtime, tool, args) {
+                Ok(dir) => dir,
+                Err(err) => return tool_error(err),
+            };
+            let max_bytes = runtime.config().max_export_bytes;
+            let load = |key: &str| {
+                let path = args.get(key).and_then(|p| p.as_str()).unwrap_or_default();
+                let path = validation::validate_relative_path(path)
+                    .and_then(|path| iter_mcp_server::lineage::resolve_export_path(&dir, path))?;
+                iter_mcp_server::lineage::load_lineage_export(&path, max_bytes)
+            };
+            match load("a").and_then(|a| Ok((a, load("b")?))) {
+                Ok((a, b)) => tool_text(&iter_mcp_server::lineage::diff_lineage(&a, &b)),
+                Err(err) => tool_error(err),
+            }
+        }
+        "lineage.get" => {
+            let Some(sequence) = args.get("sequence").and_then(|s| s.as_u64()) else {
+                return tool_error(McpError::BadRequest {
+                    message: "sequence is required and must be a non-negative integer".to_string(),
+                });
+            };
+            match runtime.lineage_entry(sequence) {
+                Some(entry) => tool_text(&entry),
+                None => tool_error(McpError::BadRequest {
+                    message: format!("lineage sequence {} out of range", sequence),
+                }),
+            }
         }
         _ => json!({"error": {"code": 3000, "message": "Unknown tool"}}),
     }
 }
+
+/// Authorize a file-backed lineage tool and return the configured export
+/// directory (`Forbidden` when none is configured)
+fn export_dir(
+    runtime: &substrate::stub::StubRuntime,
+    tool: &str,
+    args: &serde_json::Value,
+) -> Result<std::path::PathBuf, McpError> {
+    let token = args.get("admin_token").and_then(|t| t.as_str());
+    runtime.authorize_admin(tool, token)?;
+    runtime
+        .config()
+        .export_dir
+        .clone()
+        .ok_or_else(|| McpError::Forbidden {
+            operation: tool.to_string(),
+        })
+}
+
+/// Tools whose `belief`/`energy` arguments take `default_belief`/`default_energy`
+const NODE_INPUT_TOOLS: &[&str] = &["node.create", "node.validate", "graph.append_chain"];
+
+/// Fill omitted `belief`/`energy` from the configured defaults, if any.
+///
+/// Returns `None` when nothing was filled in, so callers keep the original
+/// arguments. Without configured defaults both arguments stay required.
+fn with_node_defaults(
+    config: &substrate::config::StubRuntimeConfig,
+    tool: &str,
+    args: &serde_json::Value,
+) -> Option<serde_json::Value> {
+    if !NODE_INPUT_TOOLS.contains(&tool) {
+        return None;
+    }
+    let mut filled = args.as_object()?.clone();
+    for (name, default) in [
+        ("belief", config.default_belief),
+        ("energy", config.default_energy),
+    ] {
+        if let Some(default) = default {
+            filled.entry(name).or_insert(json!(default));
+        }
+    }
+    (filled.len() != args.as_object()?.len()).then_some(serde_json::Value::Object(filled))
+}
+
+/// `belief` and `energy` for node creation, defaulted from config where allowed
+fn node_inputs(
+    config: &substrate::config::StubRuntimeConfig,
+    args: &serde_json::Value,
+) -> Result<(f64, f64), McpError> {
+    let input = |name: &str, default: Option<f64>| {
+        args.get(name)
+            .and_then(|v| v.as_f64())
+            .or(default)
+            .ok_or_else(|| McpError::BadRequest {
+                message: format!("{} is required and must be a number", name),
+            })
+    };
+    Ok((
+        input("belief", config.default_belief)?,
+        input("energy", config.default_energy)?,
+    ))
+}
+
+/// Wrap a serializable result as MCP text content.
+///
+/// A serialization failure becomes a scrubbed `substrate_error` rather than
+/// leaking the value's internals (or panicking the server).
+fn tool_text<T: serde::Serialize>(value: &T) -> serde_json::Value {
+    match serde_json::to_string(value) {
+        Ok(text) => json!({"content": [{"type": "text", "text": text}]}),
+        Err(_) => tool_error(McpError::SubstrateError {
+            message: "result serialization failed".to_string(),
+        }),
+    }
+}
+
+/// Render an `McpError` as a tool error payload.
+fn tool_error(err: McpError) -> serde_json::Value {
+    json!({"error": {"code": err.code(), "message": err.to_string()}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate::stub::StubRuntime;
+
+    #[test]
+    fn ping_succeeds_on_fresh_server() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+
+        let result = handle_stub_request(&mut runtime, &mut session, "ping", &req);
+
+        assert_eq!(result["pong"], true);
+        assert!(result["server_time_unix"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn ping_bypasses_runtime_write_lock() {
+        use iter_mcp_server::SharedStubRuntime;
+        use std::sync::{mpsc, Arc};
+
+        let shared = Arc::new(SharedStubRuntime::default());
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let holder = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                let _guard = shared.write().unwrap();
+                locked_tx.send(()).unwrap();
+                let _ = release_rx.recv_timeout(Duration::from_secs(5));
+            })
+        };
+        locked_rx.recv().unwrap();
+
+        // Answered through the locking dispatch path while the writer still
+        // holds the lock; acquiring it here would block for the full 5 s
+        let mut session = Session::default();
+        let req = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        let started = Instant::now();
+        let acquire = || shared.write().unwrap();
+        let result = handle_request_with(acquire, &mut session, "ping", &req);
+        assert_eq!(result["pong"], true);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            shared.write_queue_depth(),
+            1,
+            "ping must not queue for the lock"
+        );
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn server_health_reports_the_shared_write_load() {
+        use iter_mcp_server::{LoadLevel, SharedStubRuntime};
+        use std::sync::Arc;
+
+        let shared = Arc::new(
+            SharedStubRuntime::default()
+                .with_max_concurrent_writes(2)
+                .unwrap(),
+        );
+        let held = shared.write().unwrap();
+        let waiter = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || drop(shared.write()))
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while shared.write_queue_depth() < 2 {
+            assert!(Instant::now() < deadline, "writer never queued");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        // What a shared transport does on receipt, before queueing itself
+        let mut session = Session {
+            write_load: shared.load(),
+            ..Session::default()
+        };
+        assert_eq!(session.write_load, LoadLevel::Saturated);
+        drop(held);
+        waiter.join().unwrap();
+
+        let req = json!({"params": {"name": "server.health", "arguments": {}}});
+        let acquire = || shared.write().unwrap();
+        let result = handle_request_with(acquire, &mut session, "tools/call", &req);
+        let health: serde_json::Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(health["load"], "saturated");
+
+        let idle = call_tool(&mut StubRuntime::new(), "server.health", json!({}));
+        assert!(idle["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains(r#""load":"ok""#));
+    }
+
+    #[test]
+    fn progress_notifications_track_multi_step_propagation() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mut session = Session {
+            notifications: Some(NotificationSink(Box::new({
+                let sent = Rc::clone(&sent);
+                move |message| sent.borrow_mut().push(message)
+            }))),
+            ..Session::default()
+        };
+        let mut runtime = StubRuntime::new();
+        let propagate = |meta: serde_json::Value| json!({"params": {"name": "edge.propagate", "arguments": {"steps": 200}, "_meta": meta}});
+
+        handle_stub_request(
+            &mut runtime,
+            &mut session,
+            "tools/call",
+            &propagate(json!({})),
+        );
+        assert!(sent.borrow().is_empty(), "progress is opt-in");
+
+        let req = propagate(json!({"progressToken": "prop-1"}));
+        let result = handle_stub_request(&mut runtime, &mut session, "tools/call", &req);
+        assert!(result.get("error").is_none());
+
+        let sent = sent.borrow();
+        let progress: Vec<u64> = sent
+            .iter()
+            .map(|n| {
+                assert_eq!(n["method"], "notifications/progress");
+                assert_eq!(n["params"]["progressToken"], "prop-1");
+                assert_eq!(n["params"]["total"], 200);
+                n["params"]["progress"].as_u64().unwrap()
+            })
+            .collect();
+        assert!(progress.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(progress.last(), Some(&200));
+    }
+
+    #[test]
+    fn tool_allowlist_hides_and_blocks_disabled_tools() {
+        let config = substrate::config::StubRuntimeConfig {
+            enabled_tools: Some(
+                ["node.query", "governance.status"]
+                    .map(str::to_string)
+                    .into(),
+            ),
+            ..Default::default()
+        };
+        let mut runtime = StubRuntime::with_config(config);
+        let mut session = Session::default();
+
+        let listed = handle_stub_request(&mut runtime, &mut session, "tools/list", &json!({}));
+        let names: Vec<&str> = listed["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["node.query", "governance.status"]);
+
+        // Even malformed arguments get method-not-found, not a validation error
+        let created = call_tool(&mut runtime, "node.create", json!({"belief": "x"}));
+        assert_eq!(created["error"]["code"], -32601);
+        assert_eq!(runtime.governor_status().node_count, 0);
+
+        let queried = call_tool(&mut runtime, "node.query", json!({"node_id": "0"}));
+        assert_ne!(queried["error"]["code"], -32601);
+        let status = call_tool(&mut runtime, "governance.status", json!({}));
+        assert!(status.get("error").is_none());
+    }
+
+    /// Collect paths of `mode`/`_mode` keys, skipping propagation artifacts
+    /// (whose `mode` is part of the artifact contract).
+    fn mode_keys(value: &serde_json::Value, path: &str, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let is_artifact =
+                    map.contains_key("propagation_checksum") && map.contains_key("decision_id");
+                for (key, child) in map {
+                    if (key == "mode" && !is_artifact) || key == "_mode" {
+                        found.push(format!("{}.{}", path, key));
+                    }
+                    mode_keys(child, &format!("{}.{}", path, key), found);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    mode_keys(item, &format!("{}[{}]", path, i), found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn mode_is_negotiated_once_and_never_in_tool_results() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let init = handle_stub_request(&mut runtime, &mut session, "initialize", &json!({}));
+        assert_eq!(
+            init["capabilities"]["experimental"]["mode"],
+            "reference-stub"
+        );
+
+        let a = runtime.create_node(0.4, 1.0).id.to_string();
+        let b = runtime.create_node(0.6, 1.0).id.to_string();
+        let edge = call_tool(
+            &mut runtime,
+            "edge.bind",
+            json!({"src": a, "dst": b, "weight": 0.5}),
+        );
+        let edge_id =
+            serde_json::from_str::<serde_json::Value>(edge["content"][0]["text"].as_str().unwrap())
+                .unwrap()["id"]
+                .to_string();
+
+        for tool in tool_definitions().as_array().unwrap() {
+            let name = tool["name"].as_str().unwrap();
+            let args = match name {
+                "node.create" | "node.validate" => json!({"belief": 0.5, "energy": 1.0}),
+                "node.query" | "node.provenance" | "esv.audit" => json!({"node_id": a}),
+                "node.query_at" => json!({"node_id": a, "tick": 0}),
+                "node.mutate" => json!({"node_id": a, "delta": 0.1}),
+                "node.mutate_where" => json!({"max_belief": 0.5, "delta": 0.1}),
+                "node.lock" => json!({"node_id": a, "locked": false}),
+                "node.patch" => json!({"node_id": a, "patch": {}}),
+                "node.compare" => json!({"a": a, "b": b}),
+                "node.belief_histogram" => json!({"buckets": 4}),
+                "edge.bind" => json!({"src": a, "dst": b, "weight": 0.5}),
+                "graph.append_chain" => json!({"belief": 0.5, "energy": 1.0, "from": a}),
+                "edge.reweight" => json!({"edge_id": edge_id, "weight": 0.7}),
+                "edge.estimate_cost" => json!({"edge_id": edge_id}),
+                "substrate.transaction" => {
+                    json!({"ops": [{"op": "query", "node_id": a.parse::<u64>().unwrap()}]})
+                }
+                "governor.rebaseline" => json!({"admin_token": "none"}),
+                "graph.export" => json!({"format": "dot", "admin_token": "none"}),
+                "graph.reachable" => json!({"src": a, "dst": b, "admin_token": "none"}),
+                "graph.import" => json!({"nodes": [{"id": 0, "belief": 0.5, "energy": 1.0}]}),
+                "admin.sessions" | "admin.force_quarantine" | "admin.clear_quarantine" => {
+                    json!({"admin_token": "none"})
+                }
+                "admin.inject_error" => {
+                    json!({"admin_token": "none", "method": "node.query", "error_code": 4000})
+                }
+                "lineage.replay_episode" => json!({"steps": [{"op": "propagate"}]}),
+                "lineage.get" | "lineage.merkle_proof" => json!({"sequence": 0}),
+                "lineage.attest" => json!({"expected_checksum": "0"}),
+                "verify.derived_state" => json!({
+                    "node_count": 0, "edge_count": 0, "total_energy": 0.0, "mean_belief": 0.0
+                }),
+                "lineage.diff" => {
+                    json!({"admin_token": "none", "a": "missing-a.json", "b": "missing-b.json"})
+                }
+                _ => json!({}),
+            };
+            let result = call_tool(&mut runtime, name, args);
+            let text = &result["content"][0]["text"];
+            let payload = match text.as_str() {
+                Some(raw) => serde_json::from_str(raw).unwrap_or(json!(raw)),
+                None => text.clone(),
+            };
+            let mut found = Vec::new();
+            mode_keys(&result, name, &mut found);
+            mode_keys(&payload, name, &mut found);
+            assert!(found.is_empty(), "mode leaked in tool result: {:?}", found);
+        }
+    }
+
+    #[test]
+    fn duplicate_request_ids_are_rejected_when_guard_is_on() {
+        let mut unguarded = Session::default();
+        assert!(unguarded.register_request_id(&json!(1)));
+        assert!(unguarded.register_request_id(&json!(1)));
+
+        let mut session = Session {
+            recent_ids: Some(RecentIds::new(2)),
+            ..Session::default()
+        };
+        assert!(session.register_request_id(&json!(1)));
+        assert!(!session.register_request_id(&json!(1)));
+        let rejected =
+            serde_json::to_value(session.error_envelope(json!(1), -32600, "Duplicate request id"))
+                .unwrap();
+        assert_eq!(rejected["error"]["code"], -32600);
+
+        // Distinct JSON types are distinct ids; the window is bounded
+        assert!(session.register_request_id(&json!("1")));
+        assert!(session.register_request_id(&json!(2)));
+        assert!(session.register_request_id(&json!(1)));
+    }
+
+    #[test]
+    fn quarantine_blocks_writes_but_allows_reads() {
+        let mut runtime = StubRuntime::new();
+        let id = runtime.create_node(0.5, 1.0).id.to_string();
+        assert!(runtime.check_coherence(0.0));
+
+        let queried = call_tool(&mut runtime, "node.query", json!({"node_id": id}));
+        assert!(queried.get("error").is_none());
+        let status = call_tool(&mut runtime, "governance.status", json!({}));
+        assert!(status.get("error").is_none());
+
+        let mutated = call_tool(
+            &mut runtime,
+            "node.mutate",
+            json!({"node_id": id, "delta": 0.1}),
+        );
+        assert_eq!(mutated["error"]["code"], 2001);
+        assert!(mutated["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("node.mutate"));
+        assert_eq!(runtime.query_node(id.parse().unwrap()).unwrap().belief, 0.5);
+    }
+
+    #[test]
+    fn per_tool_timeouts_abort_slow_tools_only() {
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            tool_timeouts: [
+                ("edge.propagate".to_string(), Duration::from_nanos(1)),
+                ("node.query".to_string(), Duration::from_secs(60)),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        });
+        let a = runtime.create_node(0.2, 1.0).id;
+        let b = runtime.create_node(0.8, 1.0).id;
+        runtime.bind_edge(a, b, 0.5);
+
+        let steps = substrate::stub::DEADLINE_CHECK_INTERVAL * 4;
+        let propagated = call_tool(&mut runtime, "edge.propagate", json!({"steps": steps}));
+        assert_eq!(propagated["error"]["code"], 4008);
+        assert_eq!(
+            runtime.lineage_entries().last().unwrap().operation,
+            "edge.propagate.aborted"
+        );
+
+        let queried = call_tool(
+            &mut runtime,
+            "node.query",
+            json!({"node_id": a.to_string()}),
+        );
+        assert!(queried.get("error").is_none());
+    }
+
+    #[test]
+    fn server_features_follow_build_config_and_transport() {
+        let features_over = |config, long_poll| {
+            let mut runtime = StubRuntime::with_config(config);
+            let mut session = Session {
+                long_poll,
+                ..Session::default()
+            };
+            let req = json!({"params": {"name": "server.features", "arguments": {}}});
+            let result = handle_stub_request(&mut runtime, &mut session, "tools/call", &req);
+            let text = result["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<serde_json::Value>(text).unwrap()["features"].clone()
+        };
+        let features = |config| features_over(config, true);
+        let has = |features: &serde_json::Value, flag: &str| {
+            features.as_array().unwrap().iter().any(|f| f == flag)
+        };
+
+        let defaults = features(substrate::config::StubRuntimeConfig::default());
+        for flag in ["transactions", "lineage_tail", "progress_notifications"] {
+            assert!(has(&defaults, flag), "missing {}", flag);
+        }
+        assert!(!has(&defaults, "node_history"));
+        assert!(!has(&defaults, "test_hooks"));
+        // STDIO refuses `lineage.tail` waits, so it never advertises tailing
+        let stdio = features_over(substrate::config::StubRuntimeConfig::default(), false);
+        assert!(!has(&stdio, "lineage_tail"));
+        assert!(has(&stdio, "transactions"));
+        assert_eq!(
+            has(&defaults, "lineage_gzip"),
+            cfg!(feature = "lineage_gzip")
+        );
+
+        let configured = features(substrate::config::StubRuntimeConfig {
+            node_history_depth: 4,
+            enabled_tools: Some(
+                ["server.features", "node.query_at"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+        assert!(has(&configured, "node_history"));
+        assert!(!has(&configured, "transactions"));
+        assert!(!has(&configured, "lineage_tail"));
+    }
+
+    #[test]
+    fn lineage_export_needs_the_admin_token_and_an_export_dir() {
+        let dir = std::env::temp_dir().join(format!("iter-export-tool-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        let runtime_with = |export_dir: Option<std::path::PathBuf>| {
+            StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+                admin_token: Some("secret".to_string()),
+                export_dir,
+                ..Default::default()
+            })
+        };
+        let export = |token: &str| json!({"admin_token": token, "path": "lineage.json"});
+
+        let mut runtime = runtime_with(Some(dir.clone()));
+        runtime.create_node(0.5, 1.0);
+        let anonymous = call_tool(&mut runtime, "lineage.export", export("wrong"));
+        assert_eq!(anonymous["error"]["code"], 4003);
+        let no_dir = call_tool(&mut runtime_with(None), "lineage.export", export("secret"));
+        assert_eq!(no_dir["error"]["code"], 4003);
+
+        let written = call_tool(&mut runtime, "lineage.export", export("secret"));
+        assert!(written.get("error").is_none(), "{written}");
+        assert!(dir.join("lineage.json").exists());
+        let again = call_tool(&mut runtime, "lineage.export", export("secret"));
+        assert_eq!(again["error"]["code"], 4000);
+
+        let diff =
+            |token: &str| json!({"admin_token": token, "a": "lineage.json", "b": "lineage.json"});
+        let refused = call_tool(&mut runtime, "lineage.diff", diff("wrong"));
+        assert_eq!(refused["error"]["code"], 4003);
+        let compared = call_tool(&mut runtime, "lineage.diff", diff("secret"));
+        assert!(compared["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("identical"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn edge_cost_estimate_matches_the_step_without_side_effects() {
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            drift_correction_threshold: Some(0.5),
+            edge_influence_rate: 0.5,
+            ..Default::default()
+        });
+        let mut call = |tool: &str, args: serde_json::Value| {
+            let result = call_tool(&mut runtime, tool, args);
+            match result["content"][0]["text"].as_str() {
+                Some(text) => serde_json::from_str::<serde_json::Value>(text).unwrap(),
+                None => result,
+            }
+        };
+        let a = call("node.create", json!({"belief": 0.9, "energy": 3.0}))["id"].to_string();
+        let b = call("node.create", json!({"belief": 0.1, "energy": 2.0}))["id"].to_string();
+        let edge = call("edge.bind", json!({"src": a, "dst": b, "weight": 0.8}))["id"].to_string();
+        let endpoints = |call: &mut dyn FnMut(&str, serde_json::Value) -> serde_json::Value| {
+            [&a, &b]
+                .iter()
+                .map(|id| {
+                    call("node.query", json!({"node_id": id}))["energy"]
+                        .as_f64()
+                        .unwrap()
+                })
+                .sum::<f64>()
+        };
+
+        let fingerprint = call("server.fingerprint", json!({}));
+        let estimate = call("edge.estimate_cost", json!({"edge_id": edge}))
+            ["estimated_energy_cost"]
+            .as_f64()
+            .unwrap();
+        assert_eq!(call("server.fingerprint", json!({})), fingerprint);
+
+        let before = endpoints(&mut call);
+        let belief = call("node.query", json!({"node_id": b}))["belief"].clone();
+        call("edge.propagate", json!({}));
+        assert_ne!(call("node.query", json!({"node_id": b}))["belief"], belief);
+        assert!((before - endpoints(&mut call) - estimate).abs() < 1e-9);
+
+        let unknown = call("edge.estimate_cost", json!({"edge_id": "999999999"}));
+        assert_eq!(unknown["error"]["code"], 4004);
+    }
+
+    #[test]
+    fn oversized_framed_response_becomes_an_error_frame() {
+        let padding = "x".repeat(iter_mcp_server::framing::MAX_FRAME_BYTES);
+        let response = RpcResponse::success(json!(7), json!({"padding": padding}));
+        let mut out = Vec::new();
+        write_framed_response(&mut out, &response);
+
+        let mut reader = out.as_slice();
+        let frame = iter_mcp_server::framing::read_frame(&mut reader)
+            .unwrap()
+            .unwrap();
+        let reply: serde_json::Value = serde_json::from_slice(&frame).unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], -32603);
+        assert!(reply.get("result").is_none());
+        assert!(iter_mcp_server::framing::read_frame(&mut reader)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn quarantine_test_hooks_need_the_flag() {
+        let admin = |allow_test_hooks| {
+            StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+                admin_token: Some("secret".to_string()),
+                allow_test_hooks,
+                ..Default::default()
+            })
+        };
+        let token = json!({"admin_token": "secret"});
+
+        let mut locked = admin(false);
+        let listed = call_tool(&mut locked, "admin.force_quarantine", token.clone());
+        assert_eq!(listed["error"]["code"], -32601);
+        assert!(!locked.is_quarantined());
+
+        let mut runtime = admin(true);
+        let id = runtime.create_node(0.5, 1.0).id.to_string();
+        let forced = call_tool(&mut runtime, "admin.force_quarantine", token.clone());
+        assert!(forced.get("error").is_none());
+        let mutated = call_tool(
+            &mut runtime,
+            "node.mutate",
+            json!({"node_id": id, "delta": 0.1}),
+        );
+        assert_eq!(mutated["error"]["code"], 2001);
+
+        let cleared = call_tool(&mut runtime, "admin.clear_quarantine", token);
+        assert!(cleared.get("error").is_none());
+        let mutated = call_tool(
+            &mut runtime,
+            "node.mutate",
+            json!({"node_id": id, "delta": 0.1}),
+        );
+        assert!(mutated.get("error").is_none());
+    }
+
+    #[test]
+    fn injected_error_fails_exactly_the_next_call() {
+        let admin = |allow_test_hooks| {
+            StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+                admin_token: Some("secret".to_string()),
+                allow_test_hooks,
+                ..Default::default()
+            })
+        };
+        let inject = json!({"admin_token": "secret", "method": "node.create", "error_code": 2000});
+        let create = json!({"belief": 0.5, "energy": 1.0});
+
+        let hook_listed = |runtime: &mut StubRuntime| {
+            let listed =
+                handle_stub_request(runtime, &mut Session::default(), "tools/list", &json!({}));
+            listed["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|t| t["name"] == "admin.inject_error")
+        };
+        let direct = |runtime: &mut StubRuntime, token: &str| {
+            let args = json!({"admin_token": token, "method": "node.create", "error_code": 2000});
+            handle_stub_tool(
+                runtime,
+                "admin.inject_error",
+                &args,
+                &RequestMeta::default(),
+                &mut |_, _| {},
+            )
+        };
+
+        let mut locked = admin(false);
+        assert!(!hook_listed(&mut locked));
+        let refused = call_tool(&mut locked, "admin.inject_error", inject.clone());
+        assert_eq!(refused["error"]["code"], -32601);
+        // Even reached directly, a disabled hook answers the same for any token
+        let (good, bad) = (direct(&mut locked, "secret"), direct(&mut locked, "guess"));
+        assert_eq!(good["error"]["code"], 4003);
+        assert_eq!(good, bad);
+        assert!(call_tool(&mut locked, "node.create", create.clone())
+            .get("error")
+            .is_none());
+
+        let mut runtime = admin(true);
+        assert!(hook_listed(&mut runtime));
+        assert_eq!(direct(&mut runtime, "guess")["error"]["code"], 4003);
+        let armed = call_tool(&mut runtime, "admin.inject_error", inject);
+        assert!(armed.get("error").is_none(), "{armed}");
+        let failed = call_tool(&mut runtime, "node.create", create.clone());
+        assert_eq!(failed["error"]["code"], 2000);
+        assert!(runtime.lineage_entries().is_empty());
+        let created = call_tool(&mut runtime, "node.create", create);
+        assert!(created.get("error").is_none());
+
+        let unknown = json!({"admin_token": "secret", "method": "node.create", "error_code": 1234});
+        let rejected = call_tool(&mut runtime, "admin.inject_error", unknown);
+        assert_eq!(rejected["error"]["code"], 4000);
+
+        let bogus = json!({"admin_token": "secret", "method": "<script>", "error_code": 2000});
+        let rejected = call_tool(&mut runtime, "admin.inject_error", bogus);
+        assert_eq!(rejected["error"]["code"], 4000);
+        assert!(!rejected.to_string().contains("<script>"));
+    }
+
+    #[test]
+    fn node_patch_changes_only_the_patched_metadata_key() {
+        let mut runtime = StubRuntime::new();
+        let node = runtime.create_node(0.4, 10.0);
+        let node_id = node.id.to_string();
+        let patch = |runtime: &mut StubRuntime, patch: serde_json::Value| {
+            let resp = call_tool(
+                runtime,
+                "node.patch",
+                json!({"node_id": node_id, "patch": patch}),
+            );
+            match resp.get("error") {
+                Some(err) => Err(err["code"].clone()),
+                None => {
+                    let text = resp["content"][0]["text"].as_str().unwrap().to_string();
+                    Ok(
+                        serde_json::from_str::<serde_json::Value>(&text).unwrap()["metadata"]
+                            .clone(),
+                    )
+                }
+            }
+        };
+
+        let initial = json!({"owner": "alice", "tags": {"team": "core", "tier": 1}});
+        assert_eq!(patch(&mut runtime, initial.clone()), Ok(initial));
+        assert_eq!(
+            patch(&mut runtime, json!({"tags": {"tier": 2}})),
+            Ok(json!({"owner": "alice", "tags": {"team": "core", "tier": 2}}))
+        );
+        assert_eq!(
+            patch(&mut runtime, json!({"owner": null})),
+            Ok(json!({"tags": {"team": "core", "tier": 2}}))
+        );
+
+        let forbidden = patch(&mut runtime, json!({"note": "energy_matrix dump"}));
+        assert_eq!(forbidden, Err(json!(4000)));
+        let oversized = "x".repeat(validation::MAX_NODE_METADATA_BYTES);
+        assert_eq!(
+            patch(&mut runtime, json!({"blob": oversized})),
+            Err(json!(4000))
+        );
+        assert_eq!(
+            patch(&mut runtime, json!({})),
+            Ok(json!({"tags": {"team": "core", "tier": 2}}))
+        );
+        let state = runtime.query_node(node.id).unwrap();
+        assert_eq!((state.belief, state.energy), (0.4, 10.0));
+    }
+
+    #[test]
+    fn lineage_tail_refuses_a_wait_over_stdio() {
+        let mut runtime = StubRuntime::new();
+        runtime.create_node(0.5, 1.0);
+
+        let tail = call_tool(&mut runtime, "lineage.tail", json!({"since": 0}));
+        assert!(tail.get("error").is_none());
+        let wait = call_tool(&mut runtime, "lineage.tail", json!({"timeout_ms": 5000}));
+        assert_eq!(wait["error"]["code"], 4000);
+    }
+
+    #[test]
+    fn policy_decider_vetoes_node_create_but_allows_queries() {
+        use iter_mcp_server::{PolicyDecider, PolicyDecision};
+
+        struct DenyCreate;
+        impl PolicyDecider for DenyCreate {
+            fn decide(&self, method: &str, _params_summary: &serde_json::Value) -> PolicyDecision {
+                if method == "node.create" {
+                    PolicyDecision::Deny {
+                        reason: "creation frozen".to_string(),
+                    }
+                } else {
+                    PolicyDecision::Allow
+                }
+            }
+        }
+
+        let mut runtime = StubRuntime::new();
+        let id = runtime.create_node(0.5, 1.0).id.to_string();
+        runtime.set_policy_decider(Box::new(DenyCreate));
+        let lineage_before = runtime.lineage_entries().len();
+
+        let created = call_tool(
+            &mut runtime,
+            "node.create",
+            json!({"belief": 0.2, "energy": 1.0}),
+        );
+        assert_eq!(created["error"]["code"], 4051);
+        assert!(created["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("creation frozen"));
+        assert_eq!(runtime.lineage_entries().len(), lineage_before);
+
+        let queried = call_tool(&mut runtime, "node.query", json!({"node_id": id}));
+        assert!(queried.get("error").is_none());
+    }
+
+    #[test]
+    fn walkthrough_prompt_is_listed_and_renders() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+
+        let listed = handle_stub_request(&mut runtime, &mut session, "prompts/list", &json!({}));
+        assert!(listed["prompts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|p| p["name"] == WALKTHROUGH_PROMPT));
+
+        let req = json!({"params": {"name": WALKTHROUGH_PROMPT}});
+        let prompt = handle_stub_request(&mut runtime, &mut session, "prompts/get", &req);
+        let text: Vec<&str> = prompt["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["content"]["text"].as_str().unwrap())
+            .collect();
+        assert!(!text.is_empty());
+        assert!(text.iter().any(|t| t.contains("node.create")));
+        assert!(text.iter().any(|t| t.contains("edge.propagate")));
+    }
+
+    #[test]
+    fn server_metrics_counts_calls_and_errors() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let call = |name: &str, args: serde_json::Value| json!({"params": {"name": name, "arguments": args}});
+        let create = call("node.create", json!({"belief": 0.5, "energy": 1.0}));
+        let bad_create = call("node.create", json!({"belief": 2.0, "energy": 1.0}));
+        let missing = call("node.query", json!({"node_id": "999999"}));
+
+        handle_stub_request(&mut runtime, &mut session, "tools/call", &create);
+        handle_stub_request(&mut runtime, &mut session, "tools/call", &create);
+        handle_stub_request(&mut runtime, &mut session, "tools/call", &bad_create);
+        handle_stub_request(&mut runtime, &mut session, "tools/call", &missing);
+        handle_stub_request(&mut runtime, &mut session, "tools/list", &json!({}));
+        handle_stub_request(&mut runtime, &mut session, "no/such/method", &json!({}));
+
+        let summary = runtime.metrics().summary();
+        let create = &summary.methods["node.create"];
+        assert_eq!((create.calls, create.errors), (3, 1));
+        assert!((create.error_rate - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(summary.methods["node.query"].errors, 1);
+        assert_eq!(summary.methods["tools/list"].errors, 0);
+        assert_eq!(summary.methods["no/such/method"].errors, 1);
+        assert_eq!((summary.total_calls, summary.total_errors), (6, 3));
+        assert_eq!(summary.error_rate, 0.5);
+    }
+
+    #[test]
+    fn structured_and_text_content_carry_the_same_node() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let args = json!({"belief": 0.25, "energy": 2.0});
+
+        let text = handle_stub_request(
+            &mut runtime,
+            &mut session,
+            "tools/call",
+            &json!({"params": {"name": "node.create", "arguments": args}}),
+        );
+        let per_request = handle_stub_request(
+            &mut runtime,
+            &mut session,
+            "tools/call",
+            &json!({"params": {"name": "node.create", "arguments": args, "_meta": {"content_format": "json"}}}),
+        );
+
+        let init =
+            json!({"params": {"capabilities": {"experimental": {"content_format": "json"}}}});
+        let ack = handle_stub_request(&mut runtime, &mut session, "initialize", &init);
+        assert_eq!(
+            ack["capabilities"]["experimental"]["content_format"],
+            "json"
+        );
+        let negotiated = handle_stub_request(
+            &mut runtime,
+            &mut session,
+            "tools/call",
+            &json!({"params": {"name": "node.create", "arguments": args}}),
+        );
+
+        assert_eq!(text["content"][0]["type"], "text");
+        let from_text: serde_json::Value =
+            serde_json::from_str(text["content"][0]["text"].as_str().unwrap()).unwrap();
+        for structured in [&per_request, &negotiated] {
+            assert_eq!(structured["content"][0]["type"], "json");
+            let node = &structured["content"][0]["json"];
+            for field in ["belief", "energy", "esv_valid"] {
+                assert_eq!(node[field], from_text[field]);
+            }
+        }
+    }
+
+    #[test]
+    fn unserializable_result_is_a_scrubbed_error() {
+        // serde_json refuses non-string map keys
+        let mut unserializable = std::collections::HashMap::new();
+        unserializable.insert((7u8, 9u8), "internal");
+
+        let result = tool_text(&unserializable);
+        assert!(result.get("content").is_none());
+        assert_eq!(result["error"]["code"], 5000);
+        let message = result["error"]["message"].as_str().unwrap();
+        assert!(!message.contains("internal") && !message.contains('('));
+    }
+
+    fn call_tool(
+        runtime: &mut StubRuntime,
+        name: &str,
+        args: serde_json::Value,
+    ) -> serde_json::Value {
+        let mut session = Session::default();
+        let req = json!({"params": {"name": name, "arguments": args}});
+        handle_stub_request(runtime, &mut session, "tools/call", &req)
+    }
+
+    #[test]
+    fn replaying_a_command_log_reproduces_the_session() {
+        let deterministic = || {
+            StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+                deterministic: true,
+                ..Default::default()
+            })
+        };
+        let session_lines = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"node.create","arguments":{"belief":0.4,"energy":1.0}}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"node.create","arguments":{"belief":0.6,"energy":1.0}}}"#,
+            "",
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"edge.bind","arguments":{"src":"0","dst":"1","weight":0.5}}}"#,
+            r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"edge.propagate","arguments":{"edge_id":"0"}}}"#,
+            r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"lineage.replay","arguments":{}}}"#,
+            "not json",
+        ];
+
+        let mut runtime = deterministic();
+        let mut session = Session::default();
+        let mut log = Vec::new();
+        let mut live = Vec::new();
+        for line in session_lines {
+            append_command(&mut log, line).unwrap();
+            if let Some(response) = respond_to_line(&mut runtime, &mut session, line) {
+                write_response(&mut live, &response);
+            }
+        }
+
+        let replay = || {
+            let mut out = Vec::new();
+            let written = replay_command_log(
+                &mut deterministic(),
+                &mut Session::default(),
+                log.as_slice(),
+                &mut out,
+            )
+            .unwrap();
+            assert_eq!(written, 7);
+            out
+        };
+        let first = replay();
+        assert_eq!(first, replay());
+        assert_eq!(first, live);
+        assert!(!String::from_utf8(first)
+            .unwrap()
+            .contains("\"error\":{\"code\":4004"));
+    }
+
+    #[test]
+    fn shutdown_flushes_lineage_wal() {
+        let path =
+            std::env::temp_dir().join(format!("iter-shutdown-wal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            lineage_wal: Some(path.clone()),
+            ..Default::default()
+        });
+        for belief in [0.1, 0.2, 0.3] {
+            call_tool(
+                &mut runtime,
+                "node.create",
+                json!({"belief": belief, "energy": 1.0}),
+            );
+        }
+
+        // Appends reach the file but are not synced; only shutdown syncs
+        assert_eq!(runtime.wal_sync_count(), 0);
+        let mut session = Session::default();
+        let req = json!({"jsonrpc": "2.0", "id": 9, "method": "shutdown"});
+        let result = handle_stub_request(&mut runtime, &mut session, "shutdown", &req);
+        assert_eq!(result["flushed"], true);
+        assert_eq!(runtime.wal_sync_count(), 1);
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let checksums: Vec<&str> = runtime
+            .lineage_entries()
+            .iter()
+            .map(|entry| entry.checksum.as_str())
+            .collect();
+        assert_eq!(written.lines().count(), checksums.len());
+        for checksum in checksums {
+            assert!(written.contains(checksum));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn governor_rescale_is_reported_in_the_triggering_response() {
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            energy_pool: substrate::config::EnergyPoolPolicy::FirstNode,
+            drift_correction_threshold: Some(0.5),
+            ..Default::default()
+        });
+        let mut session = Session::default();
+        let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{"experimental":{"warnings":true}}}}"#;
+        respond_to_line(&mut runtime, &mut session, init).unwrap();
+        let mut create = |energy: f64| {
+            let line = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                "params": {"name": "node.create", "arguments": {"belief": 0.5, "energy": energy}}});
+            let response = respond_to_line(&mut runtime, &mut session, &line.to_string()).unwrap();
+            serde_json::to_value(response).unwrap()["_meta"]["warnings"].clone()
+        };
+
+        assert!(create(100.0).is_null());
+        assert!(create(0.4).is_null());
+        // Drift crosses the threshold: the governor rescales all three nodes
+        assert_eq!(
+            create(0.4),
+            json!([
+                "governor rescaled the energy of 3 nodes after node.create (DriftMinor); see governor.corrections"
+            ])
+        );
+    }
+
+    #[test]
+    fn sanitizer_warnings_reach_opted_in_clients() {
+        let mut runtime = StubRuntime::new();
+        for _ in 0..3 {
+            runtime.create_node(0.5, 1.0);
+        }
+        let replay = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"lineage.replay","arguments":{}}}"#;
+        let warnings = |session: &mut Session, runtime: &mut StubRuntime, line: &str| {
+            let response = respond_to_line(runtime, session, line).unwrap();
+            serde_json::to_value(response).unwrap()["_meta"]["warnings"].clone()
+        };
+
+        let mut quiet = Session {
+            sanitizer: ResponseSanitizer {
+                max_array_len: Some(2),
+                ..Default::default()
+            },
+            ..Session::default()
+        };
+        assert!(warnings(&mut quiet, &mut runtime, replay).is_null());
+
+        let mut session = Session {
+            sanitizer: quiet.sanitizer,
+            ..Session::default()
+        };
+        let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{"experimental":{"warnings":true}}}}"#;
+        respond_to_line(&mut runtime, &mut session, init).unwrap();
+        assert_eq!(
+            warnings(&mut session, &mut runtime, replay),
+            json!(["array truncated from 3 to 2 entries"])
+        );
+        let replayed = handle_stub_request(
+            &mut runtime,
+            &mut session,
+            "tools/call",
+            &json!({"params": {"name": "lineage.replay", "arguments": {}}}),
+        );
+        let text = replayed["content"][0]["text"].as_str().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<serde_json::Value>>(text)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let deprecated = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"governor.status","arguments":{}}}"#;
+        let notice = warnings(&mut session, &mut runtime, deprecated);
+        assert!(notice[0]
+            .as_str()
+            .unwrap()
+            .contains("governor.status is deprecated"));
+        let ping = r#"{"jsonrpc":"2.0","id":4,"method":"ping"}"#;
+        assert!(warnings(&mut session, &mut runtime, ping).is_null());
+    }
+
+    #[test]
+    fn deprecated_alias_tools_name_their_replacement() {
+        let mut runtime = StubRuntime::new();
+        let listed = handle_stub_request(
+            &mut runtime,
+            &mut Session::default(),
+            "tools/list",
+            &json!({}),
+        );
+        let description = |name: &str| {
+            listed["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|t| t["name"] == name)
+                .and_then(|t| t["description"].as_str())
+                .unwrap()
+                .to_string()
+        };
+        assert!(description("governor.status")
+            .starts_with("Deprecated alias of governance.status (removed in 2.0.0)"));
+        assert!(!description("governance.status").contains("Deprecated"));
+    }
+
+    #[test]
+    fn pretty_responses_toggles_content_whitespace() {
+        let mut runtime = StubRuntime::new();
+        let req = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "node.create", "arguments": {"belief": 0.5, "energy": 1.0}}
+        });
+        let text = |session: &mut Session, runtime: &mut StubRuntime| {
+            let result = handle_stub_request(runtime, session, "tools/call", &req);
+            result["content"][0]["text"].as_str().unwrap().to_string()
+        };
+
+        let mut compact = Session::default();
+        assert!(!text(&mut compact, &mut runtime).contains('\n'));
+
+        let mut pretty = Session::default();
+        let init = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {"capabilities": {"experimental": {"pretty_responses": true}}}
+        });
+        let negotiated = handle_stub_request(&mut runtime, &mut pretty, "initialize", &init);
+        assert_eq!(
+            negotiated["capabilities"]["experimental"]["pretty_responses"],
+            true
+        );
+        let rendered = text(&mut pretty, &mut runtime);
+        assert!(rendered.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&rendered).unwrap()["belief"],
+            0.5
+        );
+    }
+
+    #[test]
+    fn request_split_across_reads_parses_as_one() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let mut pending = PendingRequest::default();
+
+        assert!(pending.push("{\"jsonrpc\":\"2.0\",\"id\":7,\n").is_empty());
+        let request = pending.push("\"method\":\"ping\"}\n");
+        assert_eq!(request.len(), 1);
+        let response = respond_to_line(&mut runtime, &mut session, &request[0]).unwrap();
+
+        assert!(response.error.is_none());
+        assert_eq!(response.id, json!(7));
+        assert_eq!(response.result.unwrap()["pong"], true);
+        assert_eq!(pending.finish(), None);
+    }
+
+    #[test]
+    fn pretty_printed_request_with_multi_line_array_parses_as_one() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let mut pending = PendingRequest::default();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "node.create",
+                "arguments": {"belief": 0.5, "energy": 1.0},
+                "tags": [1, "two", true, null]
+            }
+        });
+
+        let mut ready = Vec::new();
+        for line in serde_json::to_string_pretty(&request).unwrap().lines() {
+            ready.extend(pending.push(&format!("{}\n", line)));
+        }
+        assert_eq!(ready.len(), 1);
+        let response = respond_to_line(&mut runtime, &mut session, &ready[0]).unwrap();
+        assert!(response.error.is_none());
+        assert_eq!(response.id, json!(1));
+        assert_eq!(pending.finish(), None);
+    }
+
+    #[test]
+    fn truncated_line_does_not_swallow_following_requests() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let mut pending = PendingRequest::default();
+        let mut answer =
+            |request: &str| respond_to_line(&mut runtime, &mut session, request).unwrap();
+
+        let truncated = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"\n";
+        assert!(pending.push(truncated).is_empty());
+
+        let ready = pending.push("{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"ping\"}\n");
+        assert_eq!(ready.len(), 2);
+        let stale = answer(&ready[0]);
+        assert_eq!(stale.error.unwrap().code, -32700);
+        let ping = answer(&ready[1]);
+        assert_eq!(ping.id, json!(2));
+        assert_eq!(ping.result.unwrap()["pong"], true);
+
+        let ready = pending.push("{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"ping\"}\n");
+        assert_eq!(ready.len(), 1);
+        assert_eq!(answer(&ready[0]).id, json!(3));
+        assert_eq!(pending.finish(), None);
+    }
+
+    #[test]
+    fn complete_malformed_line_is_a_parse_error_immediately() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let mut pending = PendingRequest::default();
+
+        let request = pending.push("{\"jsonrpc\": }\n");
+        assert_eq!(request.len(), 1);
+        let response = respond_to_line(&mut runtime, &mut session, &request[0]).unwrap();
+        assert_eq!(response.error.unwrap().code, -32700);
+
+        // An unterminated fragment is answered at end of input
+        assert!(pending.push("{\"id\": \"abc").is_empty());
+        let rest = pending.finish().unwrap();
+        let response = respond_to_line(&mut runtime, &mut session, &rest).unwrap();
+        assert_eq!(response.error.unwrap().code, -32700);
+    }
+
+    #[test]
+    fn require_initialize_rejects_calls_before_handshake() {
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            require_initialize: true,
+            ..Default::default()
+        });
+        let mut session = Session::default();
+        let create = json!({
+            "params": {"name": "node.create", "arguments": {"belief": 0.5, "energy": 1.0}}
+        });
+
+        let early = handle_stub_request(&mut runtime, &mut session, "tools/call", &create);
+        assert_eq!(early["error"]["code"], -32002);
+        assert!(runtime.lineage_entries().is_empty());
+
+        handle_stub_request(&mut runtime, &mut session, "initialize", &json!({}));
+        let created = handle_stub_request(&mut runtime, &mut session, "tools/call", &create);
+        assert!(created.get("error").is_none(), "{created}");
+
+        // Lenient by default
+        let mut lenient = StubRuntime::new();
+        let created =
+            handle_stub_request(&mut lenient, &mut Session::default(), "tools/call", &create);
+        assert!(created.get("error").is_none());
+    }
+
+    #[test]
+    fn negotiated_version_reflects_initialize() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let negotiated = |runtime: &mut StubRuntime| {
+            let result = call_tool(runtime, "server.negotiated_version", json!({}));
+            let text = result["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<serde_json::Value>(text).unwrap()
+        };
+        assert!(negotiated(&mut runtime)["negotiated"].is_null());
+
+        let init = json!({"params": {"protocolVersion": "2025-03-26"}});
+        handle_stub_request(&mut runtime, &mut session, "initialize", &init);
+        let version = negotiated(&mut runtime);
+        assert_eq!(version["negotiated"], "2025-03-26");
+        assert_eq!(
+            version["server_supported"],
+            json!(["2024-11-05", "2025-03-26"])
+        );
+    }
+
+    #[test]
+    fn node_create_requires_inputs_unless_defaults_are_configured() {
+        let belief_only = json!({"belief": 0.3});
+
+        let mut strict = StubRuntime::new();
+        for tool in NODE_INPUT_TOOLS {
+            let rejected = call_tool(&mut strict, tool, belief_only.clone());
+            assert_eq!(rejected["error"]["code"], 4000);
+            assert!(rejected["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("energy is required"));
+        }
+        // The direct handler agrees with the transport
+        let direct = handle_stub_tool(
+            &mut strict,
+            "node.create",
+            &belief_only,
+            &RequestMeta::default(),
+            &mut |_, _| {},
+        );
+        assert_eq!(direct["error"]["code"], 4000);
+        assert!(strict.lineage_entries().is_empty());
+
+        let mut defaulted = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            default_energy: Some(2.5),
+            ..Default::default()
+        });
+        let created = call_tool(&mut defaulted, "node.create", belief_only);
+        let text = created["content"][0]["text"].as_str().unwrap();
+        let node: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(
+            (node["belief"].as_f64(), node["energy"].as_f64()),
+            (Some(0.3), Some(2.5))
+        );
+        let no_belief = call_tool(&mut defaulted, "node.create", json!({"energy": 1.0}));
+        assert_eq!(no_belief["error"]["code"], 4000);
+
+        let chained = call_tool(&mut defaulted, "graph.append_chain", json!({"belief": 0.7}));
+        let text = chained["content"][0]["text"].as_str().unwrap();
+        let link: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(link["node"]["energy"].as_f64(), Some(2.5));
+    }
+
+    #[test]
+    fn overflowing_energy_total_is_a_clean_error() {
+        let mut bounded = StubRuntime::new();
+        let near_max = json!({"belief": 0.5, "energy": f64::MAX / 2.0 * 1.5});
+        let rejected = call_tool(&mut bounded, "node.create", near_max.clone());
+        assert_eq!(rejected["error"]["code"], 4000);
+
+        // An operator lifting the cap gets an error, not NaN drift
+        let mut unbounded = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            max_node_energy: f64::MAX,
+            ..Default::default()
+        });
+        for _ in 0..2 {
+            let created = call_tool(&mut unbounded, "node.create", near_max.clone());
+            assert!(created.get("error").is_none(), "{}", created);
+        }
+        let status = call_tool(&mut unbounded, "governance.status", json!({}));
+        assert_eq!(status["error"]["code"], 5000);
+        assert!(!status.to_string().contains("NaN"));
+    }
+
+    #[test]
+    fn strict_params_rejects_undeclared_arguments() {
+        let args = json!({"belief": 0.5, "energy": 1.0, "expose_esv": true});
+
+        let mut lenient = StubRuntime::new();
+        let created = call_tool(&mut lenient, "node.create", args.clone());
+        assert!(created.get("error").is_none());
+
+        let mut strict = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            strict_params: true,
+            ..Default::default()
+        });
+        let rejected = call_tool(&mut strict, "node.create", args);
+        assert_eq!(rejected["error"]["code"], 4000);
+        assert_eq!(
+            rejected["error"]["message"],
+            "Bad request: unexpected arguments: expose_esv"
+        );
+        assert!(strict.lineage_entries().is_empty());
+    }
+
+    #[test]
+    fn node_validate_has_no_side_effects() {
+        let mut runtime = StubRuntime::new();
+
+        let ok = call_tool(
+            &mut runtime,
+            "node.validate",
+            json!({"belief": 0.5, "energy": 1.0}),
+        );
+        let bad_belief = call_tool(
+            &mut runtime,
+            "node.validate",
+            json!({"belief": 1.5, "energy": 1.0}),
+        );
+        let bad_energy = call_tool(
+            &mut runtime,
+            "node.validate",
+            json!({"belief": 0.5, "energy": -1.0}),
+        );
+
+        assert_eq!(ok["content"][0]["text"], r#"{"valid":true}"#);
+        assert_eq!(bad_belief["error"]["code"], 4000);
+        assert_eq!(bad_energy["error"]["code"], 4000);
+        assert_eq!(runtime.governor_status().node_count, 0);
+        assert!(runtime.lineage_entries().is_empty());
+    }
+
+    #[test]
+    fn node_validate_matches_node_create_verdict() {
+        for args in [
+            json!({"belief": 0.5, "energy": 1.0}),
+            json!({"belief": 0.5, "energy": 1e300}),
+        ] {
+            let mut runtime = StubRuntime::new();
+            let validated = call_tool(&mut runtime, "node.validate", args.clone());
+            let created = call_tool(&mut runtime, "node.create", args);
+            assert_eq!(validated.get("error"), created.get("error"));
+        }
+    }
+
+    #[test]
+    fn server_version_reports_crate_and_protocol_versions() {
+        let mut runtime = StubRuntime::new();
+        let result = call_tool(&mut runtime, "server.version", json!({}));
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let version: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(version["server_name"], "iter-server");
+        assert_eq!(version["server_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            version["protocol_version"],
+            iter_mcp_server::PROTOCOL_VERSION
+        );
+        assert_eq!(version["build"].as_str(), option_env!("ITER_BUILD_ID"));
+    }
+
+    #[test]
+    fn responses_carry_protocol_version_unless_opted_out() {
+        let mut session = Session::default();
+        let envelope = serde_json::to_value(session.envelope(json!(1), json!({}))).unwrap();
+        assert_eq!(
+            envelope["_meta"]["protocol_version"],
+            iter_mcp_server::PROTOCOL_VERSION
+        );
+
+        let mut runtime = StubRuntime::new();
+        let init = json!({"params": {"capabilities": {"experimental": {"response_meta": false}}}});
+        handle_stub_request(&mut runtime, &mut session, "initialize", &init);
+        let envelope = serde_json::to_value(session.envelope(json!(2), json!({}))).unwrap();
+        assert!(envelope.get("_meta").is_none());
+    }
+
+    #[test]
+    fn server_config_never_exposes_admin_token() {
+        let mut runtime = StubRuntime::with_config(substrate::config::StubRuntimeConfig {
+            admin_token: Some("s3cret".to_string()),
+            drift_tolerance: 0.25,
+            ..Default::default()
+        });
+
+        let resp = call_tool(&mut runtime, "server.config", json!({}));
+        let text = resp["content"][0]["text"].as_str().unwrap();
+
+        assert!(!text.contains("s3cret"));
+        assert!(!text.contains("admin_token"));
+        let summary: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(summary["drift_tolerance"], 0.25);
+    }
+
+    #[test]
+    fn unknown_prompt_is_bad_request() {
+        let mut runtime = StubRuntime::new();
+        let mut session = Session::default();
+        let req = json!({"params": {"name": "nope"}});
+        let resp = handle_stub_request(&mut runtime, &mut session, "prompts/get", &req);
+        assert_eq!(resp["error"]["code"], 4000);
+    }
+
+    #[test]
+    fn banner_defaults_follow_build_profile() {
+        assert!(!flag_setting(None, false));
+        assert!(flag_setting(None, true));
+    }
+
+    #[test]
+    fn banner_env_overrides_default() {
+        assert!(!flag_setting(Some("0"), true));
+        assert!(!flag_setting(Some("off"), true));
+        assert!(flag_setting(Some("TRUE"), false));
+        assert!(!flag_setting(Some("garbage"), false));
+    }
+}
@@ -0,0 +1,164 @@
+//! Per-method request metrics.
+//!
+//! Counts calls, errors, and cumulative latency per MCP method name.
+//! Only method names are recorded — never parameters or results.
+//!
+//! # Concurrency
+//!
+//! Counters are atomics behind a read-mostly map: recording a call for a
+//! method that has been seen before takes only a shared read lock, so
+//! concurrent handlers do not serialize on metrics.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct MethodCounters {
+    calls: AtomicU64,
+    errors: AtomicU64,
+    total_duration_us: AtomicU64,
+}
+
+/// Thread-safe per-method counters
+#[derive(Debug, Default)]
+pub struct MethodMetrics {
+    methods: RwLock<HashMap<String, Arc<MethodCounters>>>,
+}
+
+/// Counters for a single method
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MethodSummary {
+    /// Completed calls
+    pub calls: u64,
+    /// Calls that returned an error
+    pub errors: u64,
+    /// `errors / calls` (0.0 when no calls)
+    pub error_rate: f64,
+    /// Cumulative handling time in microseconds
+    pub total_duration_us: u64,
+}
+
+/// Snapshot returned by `server.metrics`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MetricsSummary {
+    /// Per-method counters, keyed by method (or tool) name
+    pub methods: BTreeMap<String, MethodSummary>,
+    /// Calls across all methods
+    pub total_calls: u64,
+    /// Errors across all methods
+    pub total_errors: u64,
+    /// Overall error rate (0.0 when no calls)
+    pub error_rate: f64,
+}
+
+impl MethodMetrics {
+    /// Create an empty metrics table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one completed call
+    pub fn record(&self, method: &str, success: bool, duration: Duration) {
+        let counters = self.counters(method);
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        counters
+            .total_duration_us
+            .fetch_add(micros, Ordering::Relaxed);
+    }
+
+    /// Snapshot all counters
+    pub fn summary(&self) -> MetricsSummary {
+        let methods: BTreeMap<String, MethodSummary> = self
+            .methods
+            .read()
+            .iter()
+            .map(|(name, c)| {
+                let calls = c.calls.load(Ordering::Relaxed);
+                let errors = c.errors.load(Ordering::Relaxed);
+                let summary = MethodSummary {
+                    calls,
+                    errors,
+                    error_rate: rate(errors, calls),
+                    total_duration_us: c.total_duration_us.load(Ordering::Relaxed),
+                };
+                (name.clone(), summary)
+            })
+            .collect();
+        let total_calls = methods.values().map(|m| m.calls).sum();
+        let total_errors = methods.values().map(|m| m.errors).sum();
+        MetricsSummary {
+            methods,
+            total_calls,
+            total_errors,
+            error_rate: rate(total_errors, total_calls),
+        }
+    }
+
+    fn counters(&self, method: &str) -> Arc<MethodCounters> {
+        if let Some(counters) = self.methods.read().get(method) {
+            return Arc::clone(counters);
+        }
+        Arc::clone(self.methods.write().entry(method.to_string()).or_default())
+    }
+}
+
+fn rate(errors: u64, calls: u64) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        errors as f64 / calls as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn counts_calls_and_errors_per_method() {
+        let metrics = MethodMetrics::new();
+        metrics.record("node.create", true, Duration::from_micros(10));
+        metrics.record("node.create", false, Duration::from_micros(5));
+        metrics.record("tools/list", true, Duration::from_micros(1));
+
+        let summary = metrics.summary();
+        let create = &summary.methods["node.create"];
+        assert_eq!(create.calls, 2);
+        assert_eq!(create.errors, 1);
+        assert_eq!(create.error_rate, 0.5);
+        assert_eq!(create.total_duration_us, 15);
+        assert_eq!(summary.total_calls, 3);
+        assert_eq!(summary.total_errors, 1);
+    }
+
+    #[test]
+    fn concurrent_recording_loses_no_updates() {
+        let metrics = Arc::new(MethodMetrics::new());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let metrics = Arc::clone(&metrics);
+                thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        metrics.record("node.query", i % 2 == 0, Duration::ZERO);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let summary = metrics.summary();
+        assert_eq!(summary.methods["node.query"].calls, 8_000);
+        assert_eq!(summary.methods["node.query"].errors, 4_000);
+    }
+}
@@ -0,0 +1,107 @@
+//! External Policy Hook
+//!
+//! Lets an embedding application veto tool calls before they execute, e.g.
+//! by consulting an external authorization or policy engine. The decider
+//! sees the method name and a sanitized summary of the arguments, never the
+//! raw request.
+//!
+//! # Sanitized Summary
+//!
+//! [`params_summary`] keeps argument names (sorted) and reduces values to
+//! what a policy needs: numbers and booleans as-is, strings truncated to
+//! [`MAX_SUMMARY_STRING_LEN`] characters, credential-like fields replaced by
+//! `"[redacted]"`, and nested arrays/objects replaced by their size.
+
+use serde_json::{json, Map, Value};
+
+use crate::types::McpError;
+
+/// Longest string value passed to a decider (longer values are truncated)
+pub const MAX_SUMMARY_STRING_LEN: usize = 64;
+
+/// Argument names containing any of these are redacted from summaries
+const REDACTED_NAME_PARTS: &[&str] = &["token", "secret", "password", "key"];
+
+/// Verdict returned by a [`PolicyDecider`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// Let the call proceed
+    Allow,
+    /// Refuse the call; `reason` is returned to the caller
+    Deny {
+        /// Caller-visible explanation
+        reason: String,
+    },
+}
+
+/// External policy consulted before every tool call
+pub trait PolicyDecider: Send + Sync {
+    /// Decide on `method` (the tool name) given a sanitized argument summary
+    fn decide(&self, method: &str, params_summary: &Value) -> PolicyDecision;
+}
+
+/// Sanitized view of tool arguments for a [`PolicyDecider`]
+pub fn params_summary(args: &Value) -> Value {
+    let Some(args) = args.as_object() else {
+        return json!({});
+    };
+    let summary: Map<String, Value> = args
+        .iter()
+        .map(|(name, value)| (name.clone(), summarize_value(name, value)))
+        .collect();
+    Value::Object(summary)
+}
+
+fn summarize_value(name: &str, value: &Value) -> Value {
+    let lowered = name.to_ascii_lowercase();
+    if REDACTED_NAME_PARTS
+        .iter()
+        .any(|part| lowered.contains(part))
+    {
+        return json!("[redacted]");
+    }
+    match value {
+        Value::String(s) => json!(s.chars().take(MAX_SUMMARY_STRING_LEN).collect::<String>()),
+        Value::Array(items) => json!({ "items": items.len() }),
+        Value::Object(fields) => json!({ "fields": fields.len() }),
+        other => other.clone(),
+    }
+}
+
+/// Consult `decider` for `method`, mapping a deny to `McpError::PolicyDenied`
+pub fn enforce(decider: &dyn PolicyDecider, method: &str, args: &Value) -> Result<(), McpError> {
+    match decider.decide(method, &params_summary(args)) {
+        PolicyDecision::Allow => Ok(()),
+        PolicyDecision::Deny { reason } => Err(McpError::PolicyDenied {
+            operation: method.to_string(),
+            reason,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_redacts_credentials_and_collapses_nesting() {
+        let long = "x".repeat(500);
+        let summary = params_summary(&json!({
+            "admin_token": "s3cret",
+            "belief": 0.5,
+            "note": long,
+            "ops": [1, 2, 3],
+            "nested": {"a": 1},
+        }));
+
+        assert_eq!(summary["admin_token"], "[redacted]");
+        assert_eq!(summary["belief"], 0.5);
+        assert_eq!(
+            summary["note"].as_str().unwrap().len(),
+            MAX_SUMMARY_STRING_LEN
+        );
+        assert_eq!(summary["ops"], json!({"items": 3}));
+        assert_eq!(summary["nested"], json!({"fields": 1}));
+        assert!(!summary.to_string().contains("s3cret"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Response Sanitizer
+//!
+//! Last line of defense at the MCP boundary: scrubs JSON about to leave the
+//! server of anything naming substrate internals ([`FORBIDDEN_PATTERNS`]).
+//!
+//! - Object keys containing a forbidden pattern are removed.
+//! - String values containing one are logged and passed through by default,
+//!   since legitimate text (a user's prompt, a note) may mention the words.
+//!   With [`ResponseSanitizer::redact_forbidden_values`] they are replaced by
+//!   [`REDACTED`] instead.
+//! - Arrays longer than [`ResponseSanitizer::max_array_len`] (e.g. a large
+//!   lineage listing) are truncated.
+//!
+//! Every finding is returned as a warning the transport can surface to the
+//! client (`_meta.warnings`); warnings never name the matched pattern.
+//!
+//! The server runs every tool result through a sanitizer. The stub builds
+//! results from sanitized DTOs, so the default one only ever flags
+//! caller-supplied text echoed back.
+
+use serde_json::Value;
+
+/// Substrings that must not appear in keys leaving the boundary
+pub const FORBIDDEN_PATTERNS: &[&str] = &[
+    "dag_topology",
+    "adjacency_matrix",
+    "esv_raw",
+    "energy_matrix",
+    "lineage_hash_chain",
+    "internal_state",
+];
+
+/// Replacement for redacted string values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Boundary filter for outgoing JSON (see module docs)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseSanitizer {
+    /// Replace string values containing a forbidden pattern with
+    /// [`REDACTED`] instead of only warning (off by default)
+    pub redact_forbidden_values: bool,
+    /// Keep at most this many elements of any array (`None` = unlimited)
+    pub max_array_len: Option<usize>,
+}
+
+impl ResponseSanitizer {
+    /// Scrub `value` in place, returning a warning per finding
+    pub fn sanitize_value(&self, value: &mut Value) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.sanitize_into(value, &mut warnings);
+        warnings
+    }
+
+    fn sanitize_into(&self, value: &mut Value, warnings: &mut Vec<String>) {
+        match value {
+            Value::Object(fields) => {
+                let before = fields.len();
+                fields.retain(|key, _| forbidden_pattern(key).is_none());
+                for _ in fields.len()..before {
+                    tracing::warn!("removed field naming substrate internals");
+                    warnings.push("removed a forbidden field".to_string());
+                }
+                for field in fields.values_mut() {
+                    self.sanitize_into(field, warnings);
+                }
+            }
+            Value::Array(items) => {
+                if let Some(max) = self.max_array_len.filter(|max| items.len() > *max) {
+                    warnings.push(format!(
+                        "array truncated from {} to {} entries",
+                        items.len(),
+                        max
+                    ));
+                    items.truncate(max);
+                }
+                for item in items {
+                    self.sanitize_into(item, warnings);
+                }
+            }
+            Value::String(text) => {
+                let Some(pattern) = forbidden_pattern(text) else {
+                    return;
+                };
+                if self.redact_forbidden_values {
+                    tracing::warn!("redacted string value containing {}", pattern);
+                    *text = REDACTED.to_string();
+                    warnings.push("redacted a string value".to_string());
+                } else {
+                    tracing::warn!("string value contains forbidden pattern {}", pattern);
+                    warnings.push("string value contains a forbidden pattern".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// First forbidden pattern contained in `text`, if any
+pub(crate) fn forbidden_pattern(text: &str) -> Option<&'static str> {
+    let lowered = text.to_ascii_lowercase();
+    FORBIDDEN_PATTERNS
+        .iter()
+        .copied()
+        .find(|pattern| lowered.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn forbidden_values_are_redacted_only_when_enabled() {
+        let response = json!({
+            "prompt": "show me the energy_matrix",
+            "energy_matrix": [[1.0]],
+            "notes": ["fine", "dump internal_state"],
+        });
+
+        let mut lenient = response.clone();
+        let warnings = ResponseSanitizer::default().sanitize_value(&mut lenient);
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(lenient["prompt"], "show me the energy_matrix");
+        assert!(lenient.get("energy_matrix").is_none());
+
+        let mut strict = response;
+        let sanitizer = ResponseSanitizer {
+            redact_forbidden_values: true,
+            ..Default::default()
+        };
+        assert_eq!(sanitizer.sanitize_value(&mut strict).len(), 3);
+        assert_eq!(
+            strict,
+            json!({"prompt": REDACTED, "notes": ["fine", REDACTED]})
+        );
+    }
+
+    #[test]
+    fn long_arrays_are_truncated_with_a_warning() {
+        let sanitizer = ResponseSanitizer {
+            max_array_len: Some(2),
+            ..Default::default()
+        };
+        let mut value = json!({"entries": [1, 2, 3, 4], "pair": [1, 2]});
+        let warnings = sanitizer.sanitize_value(&mut value);
+        assert_eq!(value, json!({"entries": [1, 2], "pair": [1, 2]}));
+        assert_eq!(warnings, vec!["array truncated from 4 to 2 entries"]);
+    }
+}
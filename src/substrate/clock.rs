@@ -0,0 +1,107 @@
+//! Time source for the stub runtime.
+//!
+//! Every time-dependent feature (lineage timestamps, tenant activity,
+//! governance sampling, time-based decay, request and tool deadlines,
+//! lineage tail timeouts) reads time through a [`Clock`], so tests can swap
+//! in a [`MockClock`] and advance time on command instead of sleeping.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of wall-clock and monotonic time
+pub trait Clock: Send + Sync {
+    /// Wall-clock time in Unix milliseconds (may jump backwards)
+    fn now_unix_ms(&self) -> u64;
+
+    /// Logical tick: monotonic milliseconds since an arbitrary origin.
+    /// Only differences are meaningful; never goes backwards.
+    fn tick(&self) -> u64;
+}
+
+/// The real clock: `SystemTime` for wall time, `Instant` for ticks
+#[derive(Debug)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    /// Clock whose ticks count from now
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_unix_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64)
+    }
+
+    fn tick(&self) -> u64 {
+        self.origin.elapsed().as_millis() as u64
+    }
+}
+
+/// Manually driven clock for tests; time moves only when told to
+#[derive(Debug, Default)]
+pub struct MockClock {
+    unix_ms: AtomicU64,
+    tick: AtomicU64,
+}
+
+impl MockClock {
+    /// Clock reading `unix_ms` wall time at tick 0
+    pub fn new(unix_ms: u64) -> Self {
+        Self {
+            unix_ms: AtomicU64::new(unix_ms),
+            tick: AtomicU64::new(0),
+        }
+    }
+
+    /// Move wall time and the tick forward by `ms`
+    pub fn advance(&self, ms: u64) {
+        self.unix_ms.fetch_add(ms, Ordering::SeqCst);
+        self.tick.fetch_add(ms, Ordering::SeqCst);
+    }
+
+    /// Set wall time only (e.g. to simulate an NTP step backwards)
+    pub fn set_unix_ms(&self, unix_ms: u64) {
+        self.unix_ms.store(unix_ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_unix_ms(&self) -> u64 {
+        self.unix_ms.load(Ordering::SeqCst)
+    }
+
+    fn tick(&self) -> u64 {
+        self.tick.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_moves_only_on_command() {
+        let clock = MockClock::new(1_000);
+        assert_eq!((clock.now_unix_ms(), clock.tick()), (1_000, 0));
+
+        clock.advance(250);
+        assert_eq!((clock.now_unix_ms(), clock.tick()), (1_250, 250));
+
+        clock.set_unix_ms(500);
+        assert_eq!((clock.now_unix_ms(), clock.tick()), (500, 250));
+    }
+}
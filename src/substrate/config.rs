@@ -0,0 +1,725 @@
+//! Stub runtime configuration.
+//!
+//! All fields have conservative defaults that preserve the original stub
+//! behavior; construct with `StubRuntimeConfig::default()` and override
+//! only what an experiment needs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::stub::DRIFT_TOLERANCE;
+use crate::types::McpError;
+
+/// How the conserved energy pool (drift baseline) is initialized.
+///
+/// Drift is reported as `current_total - initial_total`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "policy", content = "value")]
+pub enum EnergyPoolPolicy {
+    /// Pool is set from the first node's energy and never grows.
+    /// Every later `node.create` therefore shows up as drift.
+    FirstNode,
+    /// Pool grows by each created node's energy (creation is not drift).
+    #[default]
+    SumOfNodes,
+    /// Pool is a declared constant; drift is measured against it from the start.
+    Fixed(f64),
+}
+
+/// How node IDs are allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdStrategy {
+    /// Process-wide increasing counter (guessable)
+    #[default]
+    Sequential,
+    /// Random 53-bit IDs in `[2^52, 2^53)`: hard to enumerate, exact in JSON numbers
+    Opaque,
+}
+
+/// Which endpoint(s) of an edge its influence moves.
+///
+/// Only beliefs move, so energy (and drift) is unaffected in every mode.
+/// `Forward` and `Reverse` pull one endpoint and do not conserve total
+/// belief; `Bidirectional` applies equal and opposite pulls, so total belief
+/// is conserved except where a clamp to `[0, 1]` absorbs part of a pull.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropagationDirection {
+    /// Destination moves toward source (`src → dst`)
+    #[default]
+    Forward,
+    /// Source moves toward destination (`dst → src`)
+    Reverse,
+    /// Both endpoints move toward each other by the same amount
+    Bidirectional,
+}
+
+/// Default number of lineage entries per shard
+pub const DEFAULT_LINEAGE_SHARD_SIZE: usize = 1024;
+
+/// Largest accepted `belief_precision` (f64 carries ~15 significant decimal digits)
+pub const MAX_BELIEF_PRECISION: u32 = 15;
+
+/// Default coherence level below which the runtime quarantines
+pub const DEFAULT_COHERENCE_THRESHOLD: f64 = 0.97;
+
+/// Default minimum ESV compliance score for a node to be reported valid
+/// (every constraint must hold)
+pub const DEFAULT_ESV_THRESHOLD: f64 = 1.0;
+
+/// Default cap on a single `lineage.export` file (256 MiB)
+pub const DEFAULT_MAX_EXPORT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Governance snapshots retained for `governor.history`
+pub const GOVERNANCE_HISTORY_CAPACITY: usize = 256;
+
+/// Tools that exist only for exercising client error handling; reachable
+/// only with `StubRuntimeConfig::allow_test_hooks`
+pub const TEST_HOOK_TOOLS: &[&str] = &[
+    "admin.force_quarantine",
+    "admin.clear_quarantine",
+    "admin.inject_error",
+];
+
+/// Stub runtime configuration
+#[derive(Debug, Clone)]
+pub struct StubRuntimeConfig {
+    /// Energy-pool initialization policy
+    pub energy_pool: EnergyPoolPolicy,
+    /// Shared secret for privileged (admin-gated) tools.
+    /// `None` disables privileged tools entirely. Never exposed in responses.
+    pub admin_token: Option<String>,
+    /// Lineage entries per shard for `lineage.shards` (zero is treated as one)
+    pub lineage_shard_size: usize,
+    /// Drift magnitude above which the governor rescales node energies back
+    /// to the baseline after each operation. `None` disables auto-correction.
+    pub drift_correction_threshold: Option<f64>,
+    /// Coherence below this level triggers quarantine; must be in `[0, 1]`
+    pub coherence_threshold: f64,
+    /// Minimum ESV compliance score (fraction of constraint checks passed)
+    /// for `esv.audit` to report a node valid, in `[0, 1]`
+    pub esv_threshold: f64,
+    /// Node ID allocation strategy
+    pub id_strategy: IdStrategy,
+    /// Round beliefs to this many decimal places after every write (max 15).
+    /// `None` keeps full precision.
+    pub belief_precision: Option<u32>,
+    /// Per-step pull of each belief toward 0.5, in `[0, 1]` (0.0 = no decay).
+    /// Incident edge weight damps the pull on connected nodes.
+    pub belief_decay_rate: f64,
+    /// Apply decay once per this many milliseconds of clock time (`>= 1`)
+    /// instead of once per step: a step compounds the whole periods elapsed
+    /// on the runtime's `Clock` since the last one, carrying the remainder.
+    /// `None` decays every step regardless of time.
+    pub belief_decay_interval_ms: Option<u64>,
+    /// Per-step pull of each edge's destination belief toward its source
+    /// belief, scaled by edge weight, in `[0, 1]` (0.0 = edges carry no influence)
+    pub edge_influence_rate: f64,
+    /// Which endpoint(s) edge influence moves
+    pub propagation_direction: PropagationDirection,
+    /// Cap on how far edge influence may move one belief in a single step
+    /// (`> 0`), applied to the summed pull before the `[0, 1]` clamp.
+    ///
+    /// Models inertia: a heavy edge still converges, but over several steps
+    /// instead of one swing. Smaller per-step moves also keep coherence
+    /// changes gradual, so a cap can delay (never cause) a quarantine trip.
+    /// `None` applies the full pull.
+    pub max_belief_change_per_step: Option<f64>,
+    /// Cap on outgoing edges each node propagates along per step (`>= 1`).
+    ///
+    /// Edges are taken in edge-ID order; those beyond the cap are deferred,
+    /// and the next step resumes where the previous one stopped (wrapping
+    /// around), so every edge is visited within `ceil(out_degree / cap)` steps.
+    /// `None` propagates along every edge each step.
+    pub max_fanout_per_step: Option<usize>,
+    /// Absolute drift tolerated before `drift_ok` turns false (>= 0)
+    pub drift_tolerance: f64,
+    /// Seed for `IdStrategy::Opaque`; makes opaque IDs reproducible per runtime.
+    /// `None` draws fresh random keys.
+    pub seed: Option<u64>,
+    /// Append every lineage entry to this JSONL file as it is recorded
+    /// (flushed per entry). `None` keeps lineage in memory only.
+    pub lineage_wal: Option<PathBuf>,
+    /// Directory `lineage.export` writes into (and `lineage.diff` reads
+    /// from). `None` disables both tools.
+    pub export_dir: Option<PathBuf>,
+    /// Maximum work units (nodes + edges) one propagation step may process.
+    /// A step over budget is refused with `McpError::Stalled` before it runs.
+    /// `None` leaves steps unbounded.
+    pub step_work_budget: Option<u64>,
+    /// Tool allowlist. When set, only these tools are listed and callable;
+    /// every other tool is rejected as method-not-found. `None` enables all.
+    pub enabled_tools: Option<HashSet<String>>,
+    /// Minimum spacing between governance snapshots for `governor.history`
+    /// (`>= 1`). Snapshots are taken as requests arrive, not on a timer.
+    /// `None` disables sampling.
+    pub governance_sample_interval_ms: Option<u64>,
+    /// Snapshots of `(tick, belief, energy)` kept per node for
+    /// `node.query_at`, oldest evicted first (0 = history disabled)
+    pub node_history_depth: usize,
+    /// Refuse belief mutations whose nominal cost (`|Δbelief|` energy units)
+    /// exceeds the node's energy, instead of applying them for free
+    pub energy_enforcement: bool,
+    /// Reject `tools/call` arguments the tool's `inputSchema` does not declare.
+    /// Off by default: unknown arguments are ignored.
+    pub strict_params: bool,
+    /// Reject every method except the handshake (`initialize`,
+    /// `notifications/initialized`, `ping`) until `initialize` has been seen.
+    /// Off by default: clients that skip the handshake keep working.
+    pub require_initialize: bool,
+    /// Largest `lineage.export` file, in bytes on disk (`>= 1`); larger
+    /// exports are refused before anything is written
+    pub max_export_bytes: u64,
+    /// Largest energy a single node may be created or imported with
+    /// (finite, `> 0`). Raising it far above the default lets the energy
+    /// total overflow, which `governance.status` then reports as an error.
+    pub max_node_energy: f64,
+    /// Belief used when `node.create`/`node.validate` omit `belief`.
+    /// `None` (the default) makes the argument required.
+    pub default_belief: Option<f64>,
+    /// Energy used when `node.create`/`node.validate` omit `energy`.
+    /// `None` (the default) makes the argument required.
+    pub default_energy: Option<f64>,
+    /// Per-tool time limit, measured from receipt. Combined with any
+    /// `_meta.deadline_ms` (the earlier wins) and honored by multi-step tools.
+    pub tool_timeouts: HashMap<String, Duration>,
+    /// Time limit for tools without a `tool_timeouts` entry (`None` = unlimited)
+    pub default_tool_timeout: Option<Duration>,
+    /// Expose the test-only hooks in [`TEST_HOOK_TOOLS`] (still admin-gated).
+    /// Off by default: without it those tools are unlisted and unreachable.
+    pub allow_test_hooks: bool,
+    /// Omit wall-clock data (lineage `timestamp_unix_ms`) and number
+    /// sequential node/edge IDs per runtime from 0, so repeated runs produce
+    /// byte-identical output
+    pub deterministic: bool,
+}
+
+/// Sanitized view of the active configuration (`server.config`).
+///
+/// Secrets are never included; `admin_enabled` only reports whether
+/// privileged tools are reachable. The runtime mode is not repeated here:
+/// it is negotiated once in `initialize` (`capabilities.experimental.mode`).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConfigSummary {
+    /// Energy-pool initialization policy
+    pub energy_pool: EnergyPoolPolicy,
+    /// Node ID allocation strategy
+    pub id_strategy: IdStrategy,
+    /// Opaque-ID seed, if fixed
+    pub seed: Option<u64>,
+    /// Drift tolerance for `drift_ok`
+    pub drift_tolerance: f64,
+    /// Drift auto-correction threshold, if enabled
+    pub drift_correction_threshold: Option<f64>,
+    /// Coherence quarantine threshold
+    pub coherence_threshold: f64,
+    /// ESV validity threshold
+    pub esv_threshold: f64,
+    /// Belief rounding precision, if enabled
+    pub belief_precision: Option<u32>,
+    /// Per-step belief decay rate
+    pub belief_decay_rate: f64,
+    /// Clock time per decay period, if decay is time-based
+    pub belief_decay_interval_ms: Option<u64>,
+    /// Per-step edge influence rate
+    pub edge_influence_rate: f64,
+    /// Edge influence direction
+    pub propagation_direction: PropagationDirection,
+    /// Per-step belief change cap, if any
+    pub max_belief_change_per_step: Option<f64>,
+    /// Per-node outgoing edge cap per step, if any
+    pub max_fanout_per_step: Option<usize>,
+    /// Lineage entries per shard
+    pub lineage_shard_size: usize,
+    /// Whether privileged tools are enabled
+    pub admin_enabled: bool,
+    /// Whether lineage is mirrored to a write-ahead file (path not disclosed)
+    pub lineage_wal_enabled: bool,
+    /// Whether an export directory is configured (path not disclosed)
+    pub export_dir_enabled: bool,
+    /// Per-step work budget, if any
+    pub step_work_budget: Option<u64>,
+    /// Allowlisted tools (sorted), if the tool surface is restricted
+    pub enabled_tools: Option<Vec<String>>,
+    /// Governance snapshot interval, if sampling is enabled
+    pub governance_sample_interval_ms: Option<u64>,
+    /// Per-node history depth (0 = disabled)
+    pub node_history_depth: usize,
+    /// Whether mutations must be affordable from node energy
+    pub energy_enforcement: bool,
+    /// Whether undeclared tool arguments are rejected
+    pub strict_params: bool,
+    /// Whether calls before `initialize` are rejected
+    pub require_initialize: bool,
+    /// Largest `lineage.export` file in bytes
+    pub max_export_bytes: u64,
+    /// Largest energy accepted for a single node
+    pub max_node_energy: f64,
+    /// Belief applied when `node.create` omits it (`None` = required)
+    pub default_belief: Option<f64>,
+    /// Energy applied when `node.create` omits it (`None` = required)
+    pub default_energy: Option<f64>,
+    /// Per-tool time limits in milliseconds
+    pub tool_timeouts_ms: BTreeMap<String, u64>,
+    /// Fallback time limit in milliseconds, if any
+    pub default_tool_timeout_ms: Option<u64>,
+    /// Whether test-only hooks are reachable
+    pub allow_test_hooks: bool,
+    /// Whether wall-clock data is omitted
+    pub deterministic: bool,
+}
+
+impl Default for StubRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            energy_pool: EnergyPoolPolicy::default(),
+            admin_token: None,
+            lineage_shard_size: DEFAULT_LINEAGE_SHARD_SIZE,
+            drift_correction_threshold: None,
+            coherence_threshold: DEFAULT_COHERENCE_THRESHOLD,
+            esv_threshold: DEFAULT_ESV_THRESHOLD,
+            id_strategy: IdStrategy::default(),
+            belief_precision: None,
+            belief_decay_rate: 0.0,
+            belief_decay_interval_ms: None,
+            edge_influence_rate: 0.0,
+            propagation_direction: PropagationDirection::default(),
+            max_belief_change_per_step: None,
+            max_fanout_per_step: None,
+            drift_tolerance: DRIFT_TOLERANCE,
+            seed: None,
+            lineage_wal: None,
+            export_dir: None,
+            step_work_budget: None,
+            enabled_tools: None,
+            governance_sample_interval_ms: None,
+            node_history_depth: 0,
+            energy_enforcement: false,
+            strict_params: false,
+            require_initialize: false,
+            max_export_bytes: DEFAULT_MAX_EXPORT_BYTES,
+            max_node_energy: crate::validation::MAX_ENERGY,
+            default_belief: None,
+            default_energy: None,
+            tool_timeouts: HashMap::new(),
+            default_tool_timeout: None,
+            allow_test_hooks: false,
+            deterministic: false,
+        }
+    }
+}
+
+impl StubRuntimeConfig {
+    /// Sanitized summary (no secrets)
+    pub fn summary(&self) -> ConfigSummary {
+        ConfigSummary {
+            energy_pool: self.energy_pool,
+            id_strategy: self.id_strategy,
+            seed: self.seed,
+            drift_tolerance: self.drift_tolerance,
+            drift_correction_threshold: self.drift_correction_threshold,
+            coherence_threshold: self.coherence_threshold,
+            esv_threshold: self.esv_threshold,
+            belief_precision: self.belief_precision,
+            belief_decay_rate: self.belief_decay_rate,
+            belief_decay_interval_ms: self.belief_decay_interval_ms,
+            edge_influence_rate: self.edge_influence_rate,
+            propagation_direction: self.propagation_direction,
+            max_belief_change_per_step: self.max_belief_change_per_step,
+            max_fanout_per_step: self.max_fanout_per_step,
+            lineage_shard_size: self.lineage_shard_size,
+            admin_enabled: self.admin_token.is_some(),
+            lineage_wal_enabled: self.lineage_wal.is_some(),
+            export_dir_enabled: self.export_dir.is_some(),
+            step_work_budget: self.step_work_budget,
+            enabled_tools: self.enabled_tools.as_ref().map(|tools| {
+                let mut tools: Vec<String> = tools.iter().cloned().collect();
+                tools.sort();
+                tools
+            }),
+            governance_sample_interval_ms: self.governance_sample_interval_ms,
+            node_history_depth: self.node_history_depth,
+            energy_enforcement: self.energy_enforcement,
+            strict_params: self.strict_params,
+            require_initialize: self.require_initialize,
+            max_export_bytes: self.max_export_bytes,
+            max_node_energy: self.max_node_energy,
+            default_belief: self.default_belief,
+            default_energy: self.default_energy,
+            tool_timeouts_ms: self
+                .tool_timeouts
+                .iter()
+                .map(|(tool, limit)| (tool.clone(), duration_ms(*limit)))
+                .collect(),
+            default_tool_timeout_ms: self.default_tool_timeout.map(duration_ms),
+            allow_test_hooks: self.allow_test_hooks,
+            deterministic: self.deterministic,
+        }
+    }
+
+    /// Time limit for `tool` (`tool_timeouts`, else `default_tool_timeout`)
+    pub fn tool_timeout(&self, tool: &str) -> Option<Duration> {
+        self.tool_timeouts
+            .get(tool)
+            .copied()
+            .or(self.default_tool_timeout)
+    }
+
+    /// Whether `tool` is reachable under the `enabled_tools` allowlist
+    /// (test hooks additionally require `allow_test_hooks`)
+    pub fn tool_enabled(&self, tool: &str) -> bool {
+        if TEST_HOOK_TOOLS.contains(&tool) && !self.allow_test_hooks {
+            return false;
+        }
+        self.enabled_tools
+            .as_ref()
+            .is_none_or(|tools| tools.contains(tool))
+    }
+
+    /// Reject out-of-range settings before a runtime is built from them
+    pub fn validate(&self) -> Result<(), McpError> {
+        if !(0.0..=1.0).contains(&self.coherence_threshold) {
+            return Err(McpError::BadRequest {
+                message: "coherence_threshold must be a number in [0, 1]".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.esv_threshold) {
+            return Err(McpError::BadRequest {
+                message: "esv_threshold must be a number in [0, 1]".to_string(),
+            });
+        }
+        if !(self.drift_tolerance >= 0.0 && self.drift_tolerance.is_finite()) {
+            return Err(McpError::BadRequest {
+                message: "drift_tolerance must be a finite number >= 0".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.belief_decay_rate) {
+            return Err(McpError::BadRequest {
+                message: "belief_decay_rate must be a number in [0, 1]".to_string(),
+            });
+        }
+        if !(0.0..=1.0).contains(&self.edge_influence_rate) {
+            return Err(McpError::BadRequest {
+                message: "edge_influence_rate must be a number in [0, 1]".to_string(),
+            });
+        }
+        if self.step_work_budget == Some(0) {
+            return Err(McpError::BadRequest {
+                message: "step_work_budget must be >= 1".to_string(),
+            });
+        }
+        if self
+            .default_belief
+            .is_some_and(|belief| !(0.0..=1.0).contains(&belief))
+        {
+            return Err(McpError::BadRequest {
+                message: "default_belief must be a number in [0, 1]".to_string(),
+            });
+        }
+        if self
+            .default_energy
+            .is_some_and(|energy| !(energy >= 0.0 && energy.is_finite()))
+        {
+            return Err(McpError::BadRequest {
+                message: "default_energy must be a finite number >= 0".to_string(),
+            });
+        }
+        if !(self.max_node_energy > 0.0 && self.max_node_energy.is_finite()) {
+            return Err(McpError::BadRequest {
+                message: "max_node_energy must be a finite number > 0".to_string(),
+            });
+        }
+        if self.max_export_bytes == 0 {
+            return Err(McpError::BadRequest {
+                message: "max_export_bytes must be >= 1".to_string(),
+            });
+        }
+        if self.belief_decay_interval_ms == Some(0) {
+            return Err(McpError::BadRequest {
+                message: "belief_decay_interval_ms must be >= 1".to_string(),
+            });
+        }
+        if self.governance_sample_interval_ms == Some(0) {
+            return Err(McpError::BadRequest {
+                message: "governance_sample_interval_ms must be >= 1".to_string(),
+            });
+        }
+        if self
+            .max_belief_change_per_step
+            .is_some_and(|cap| !(cap > 0.0 && cap.is_finite()))
+        {
+            return Err(McpError::BadRequest {
+                message: "max_belief_change_per_step must be a finite number > 0".to_string(),
+            });
+        }
+        if self.max_fanout_per_step == Some(0) {
+            return Err(McpError::BadRequest {
+                message: "max_fanout_per_step must be >= 1".to_string(),
+            });
+        }
+        if self
+            .belief_precision
+            .is_some_and(|digits| digits > MAX_BELIEF_PRECISION)
+        {
+            return Err(McpError::BadRequest {
+                message: format!("belief_precision must be <= {}", MAX_BELIEF_PRECISION),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn duration_ms(limit: Duration) -> u64 {
+    u64::try_from(limit.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Portable configuration for cloning a deployment (`server.export_config`).
+///
+/// Everything in [`StubRuntimeConfig`] except `admin_token` (a secret),
+/// `lineage_wal` and `export_dir` (host-local paths); the target sets those
+/// itself. Missing
+/// fields take their defaults; unknown fields are rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigBundle {
+    /// Energy-pool initialization policy
+    pub energy_pool: EnergyPoolPolicy,
+    /// Lineage entries per shard
+    pub lineage_shard_size: usize,
+    /// Drift auto-correction threshold
+    pub drift_correction_threshold: Option<f64>,
+    /// Coherence quarantine threshold
+    pub coherence_threshold: f64,
+    /// ESV validity threshold
+    pub esv_threshold: f64,
+    /// Node ID allocation strategy
+    pub id_strategy: IdStrategy,
+    /// Belief rounding precision
+    pub belief_precision: Option<u32>,
+    /// Per-step belief decay rate
+    pub belief_decay_rate: f64,
+    /// Clock time per decay period, if decay is time-based
+    pub belief_decay_interval_ms: Option<u64>,
+    /// Per-step edge influence rate
+    pub edge_influence_rate: f64,
+    /// Edge influence direction
+    pub propagation_direction: PropagationDirection,
+    /// Per-step belief change cap
+    pub max_belief_change_per_step: Option<f64>,
+    /// Per-node outgoing edge cap per step
+    pub max_fanout_per_step: Option<usize>,
+    /// Drift tolerance for `drift_ok`
+    pub drift_tolerance: f64,
+    /// Opaque-ID seed
+    pub seed: Option<u64>,
+    /// Per-step work budget
+    pub step_work_budget: Option<u64>,
+    /// Tool allowlist (sorted)
+    pub enabled_tools: Option<Vec<String>>,
+    /// Governance snapshot interval
+    pub governance_sample_interval_ms: Option<u64>,
+    /// Per-node history depth
+    pub node_history_depth: usize,
+    /// Whether mutations must be affordable from node energy
+    pub energy_enforcement: bool,
+    /// Whether undeclared tool arguments are rejected
+    pub strict_params: bool,
+    /// Whether calls before `initialize` are rejected
+    pub require_initialize: bool,
+    /// Largest `lineage.export` file in bytes
+    pub max_export_bytes: u64,
+    /// Largest energy accepted for a single node
+    pub max_node_energy: f64,
+    /// Belief applied when `node.create` omits it (`None` = required)
+    pub default_belief: Option<f64>,
+    /// Energy applied when `node.create` omits it (`None` = required)
+    pub default_energy: Option<f64>,
+    /// Per-tool time limits in milliseconds
+    pub tool_timeouts_ms: BTreeMap<String, u64>,
+    /// Fallback time limit in milliseconds
+    pub default_tool_timeout_ms: Option<u64>,
+    /// Whether test-only hooks are reachable
+    pub allow_test_hooks: bool,
+    /// Whether wall-clock data is omitted
+    pub deterministic: bool,
+}
+
+impl Default for ConfigBundle {
+    fn default() -> Self {
+        StubRuntimeConfig::default().bundle()
+    }
+}
+
+impl StubRuntimeConfig {
+    /// Portable copy of this configuration, without secrets or host paths
+    pub fn bundle(&self) -> ConfigBundle {
+        ConfigBundle {
+            energy_pool: self.energy_pool,
+            lineage_shard_size: self.lineage_shard_size,
+            drift_correction_threshold: self.drift_correction_threshold,
+            coherence_threshold: self.coherence_threshold,
+            esv_threshold: self.esv_threshold,
+            id_strategy: self.id_strategy,
+            belief_precision: self.belief_precision,
+            belief_decay_rate: self.belief_decay_rate,
+            belief_decay_interval_ms: self.belief_decay_interval_ms,
+            edge_influence_rate: self.edge_influence_rate,
+            propagation_direction: self.propagation_direction,
+            max_belief_change_per_step: self.max_belief_change_per_step,
+            max_fanout_per_step: self.max_fanout_per_step,
+            drift_tolerance: self.drift_tolerance,
+            seed: self.seed,
+            step_work_budget: self.step_work_budget,
+            enabled_tools: self.summary().enabled_tools,
+            governance_sample_interval_ms: self.governance_sample_interval_ms,
+            node_history_depth: self.node_history_depth,
+            energy_enforcement: self.energy_enforcement,
+            strict_params: self.strict_params,
+            require_initialize: self.require_initialize,
+            max_export_bytes: self.max_export_bytes,
+            max_node_energy: self.max_node_energy,
+            default_belief: self.default_belief,
+            default_energy: self.default_energy,
+            tool_timeouts_ms: self.summary().tool_timeouts_ms,
+            default_tool_timeout_ms: self.default_tool_timeout.map(duration_ms),
+            allow_test_hooks: self.allow_test_hooks,
+            deterministic: self.deterministic,
+        }
+    }
+
+    /// Configuration described by `bundle` (no admin token, no WAL), validated
+    pub fn from_bundle(bundle: ConfigBundle) -> Result<Self, McpError> {
+        let config = Self {
+            energy_pool: bundle.energy_pool,
+            admin_token: None,
+            lineage_shard_size: bundle.lineage_shard_size,
+            drift_correction_threshold: bundle.drift_correction_threshold,
+            coherence_threshold: bundle.coherence_threshold,
+            esv_threshold: bundle.esv_threshold,
+            id_strategy: bundle.id_strategy,
+            belief_precision: bundle.belief_precision,
+            belief_decay_rate: bundle.belief_decay_rate,
+            belief_decay_interval_ms: bundle.belief_decay_interval_ms,
+            edge_influence_rate: bundle.edge_influence_rate,
+            propagation_direction: bundle.propagation_direction,
+            max_belief_change_per_step: bundle.max_belief_change_per_step,
+            max_fanout_per_step: bundle.max_fanout_per_step,
+            drift_tolerance: bundle.drift_tolerance,
+            seed: bundle.seed,
+            lineage_wal: None,
+            export_dir: None,
+            step_work_budget: bundle.step_work_budget,
+            enabled_tools: bundle
+                .enabled_tools
+                .map(|tools| tools.into_iter().collect()),
+            governance_sample_interval_ms: bundle.governance_sample_interval_ms,
+            node_history_depth: bundle.node_history_depth,
+            energy_enforcement: bundle.energy_enforcement,
+            strict_params: bundle.strict_params,
+            require_initialize: bundle.require_initialize,
+            max_export_bytes: bundle.max_export_bytes,
+            max_node_energy: bundle.max_node_energy,
+            default_belief: bundle.default_belief,
+            default_energy: bundle.default_energy,
+            tool_timeouts: bundle
+                .tool_timeouts_ms
+                .into_iter()
+                .map(|(tool, ms)| (tool, Duration::from_millis(ms)))
+                .collect(),
+            default_tool_timeout: bundle.default_tool_timeout_ms.map(Duration::from_millis),
+            allow_test_hooks: bundle.allow_test_hooks,
+            deterministic: bundle.deterministic,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(StubRuntimeConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn coherence_threshold_must_be_unit_interval() {
+        for bad in [-0.1, 1.5, f64::NAN] {
+            let config = StubRuntimeConfig {
+                coherence_threshold: bad,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{} accepted", bad);
+        }
+    }
+
+    #[test]
+    fn summary_reflects_settings_without_secrets() {
+        let config = StubRuntimeConfig {
+            seed: Some(42),
+            drift_tolerance: 0.5,
+            admin_token: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+
+        let summary = serde_json::to_value(config.summary()).unwrap();
+
+        assert_eq!(summary["seed"], 42);
+        assert_eq!(summary["drift_tolerance"], 0.5);
+        assert_eq!(summary["admin_enabled"], true);
+        assert!(summary.get("admin_token").is_none());
+        assert!(!summary.to_string().contains("hunter2"));
+    }
+
+    #[test]
+    fn belief_change_cap_must_be_positive_and_finite() {
+        for bad in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            let config = StubRuntimeConfig {
+                max_belief_change_per_step: Some(bad),
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{} accepted", bad);
+        }
+    }
+
+    #[test]
+    fn belief_precision_is_bounded() {
+        let config = StubRuntimeConfig {
+            belief_precision: Some(MAX_BELIEF_PRECISION + 1),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn config_bundle_round_trips_without_secrets() {
+        let source = StubRuntimeConfig {
+            admin_token: Some("secret".to_string()),
+            energy_pool: EnergyPoolPolicy::Fixed(42.0),
+            belief_decay_rate: 0.25,
+            propagation_direction: PropagationDirection::Bidirectional,
+            enabled_tools: Some(["node.query", "node.create"].map(String::from).into()),
+            tool_timeouts: [("edge.propagate".to_string(), Duration::from_millis(250))].into(),
+            node_history_depth: 3,
+            strict_params: true,
+            require_initialize: true,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&source.bundle()).unwrap();
+        assert!(!json.contains("secret"));
+        let cloned = StubRuntimeConfig::from_bundle(serde_json::from_str(&json).unwrap()).unwrap();
+
+        let expected = ConfigSummary {
+            admin_enabled: false,
+            ..source.summary()
+        };
+        assert_eq!(cloned.summary(), expected);
+        assert!(cloned.admin_token.is_none());
+    }
+}
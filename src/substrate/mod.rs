@@ -3,5 +3,11 @@
 //! Provides the stub substrate for public_stub mode (demonstration).
 //! Full substrate implementation is maintained separately.
 
+#[cfg(feature = "public_stub")]
+pub mod clock;
+#[cfg(feature = "public_stub")]
+pub mod config;
+#[cfg(feature = "public_stub")]
+pub mod shared;
 #[cfg(feature = "public_stub")]
 pub mod stub;
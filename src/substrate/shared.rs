@@ -0,0 +1,447 @@
+//! Shared (thread-safe) access to the stub runtime.
+//!
+//! Wraps [`StubRuntime`] in an `RwLock` so multiple transports or worker
+//! threads can share a single substrate instance.
+//!
+//! # Concurrency Control
+//!
+//! - Reads are never throttled
+//! - Writes may be bounded by `max_concurrent_writes` (holders + waiters)
+//! - When the write queue is saturated, `write()` returns
+//!   `McpError::RateLimited` immediately instead of queueing unboundedly
+//! - [`SharedStubRuntime::tail_lineage`] long-polls for new lineage entries,
+//!   woken whenever a writer releases the lock
+//! - [`SharedStubRuntime::load`] reports the approximate write queue depth
+//!   as a coarse [`LoadLevel`] so clients can back off before being refused
+
+use parking_lot::{Condvar, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use super::stub::{LineageTail, StubRuntime};
+use crate::types::McpError;
+
+/// Write queue depth at which an unlimited runtime reports `Saturated`
+pub const UNLIMITED_SATURATION_DEPTH: usize = 16;
+
+/// Coarse write-contention indicator reported by `server.health`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadLevel {
+    /// At most one writer holds or waits for the lock
+    #[default]
+    Ok,
+    /// Writers are queueing; clients should consider backing off
+    Elevated,
+    /// The write queue is full; further writes are (or soon will be) refused
+    Saturated,
+}
+
+impl LoadLevel {
+    /// Classify a write queue depth (holders + waiters) against an optional limit
+    pub fn from_queue_depth(depth: usize, limit: Option<usize>) -> Self {
+        let saturation = limit.unwrap_or(UNLIMITED_SATURATION_DEPTH).max(1);
+        if depth >= saturation {
+            LoadLevel::Saturated
+        } else if depth > 1 {
+            LoadLevel::Elevated
+        } else {
+            LoadLevel::Ok
+        }
+    }
+}
+
+/// Thread-safe handle to a stub runtime with optional write limiting
+pub struct SharedStubRuntime {
+    inner: RwLock<StubRuntime>,
+    write_permits: Option<Semaphore>,
+    max_concurrent_writes: Option<usize>,
+    queued_writers: AtomicUsize,
+    /// Bumped (and waiters woken) each time a writer releases the lock
+    write_generation: Mutex<u64>,
+    writes_released: Condvar,
+}
+
+/// Write guard holding both the runtime lock and (if limited) a write permit
+pub struct SharedWriteGuard<'a> {
+    guard: Option<RwLockWriteGuard<'a, StubRuntime>>,
+    shared: &'a SharedStubRuntime,
+    _queued: QueueSlot<'a>,
+    _permit: Option<SemaphorePermit<'a>>,
+}
+
+/// Counts one writer in the queue depth until dropped
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl<'a> QueueSlot<'a> {
+    fn enter(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::AcqRel);
+        Self(counter)
+    }
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Default for SharedStubRuntime {
+    fn default() -> Self {
+        Self::new(StubRuntime::new())
+    }
+}
+
+impl SharedStubRuntime {
+    /// Wrap a runtime with unlimited write concurrency
+    pub fn new(runtime: StubRuntime) -> Self {
+        Self {
+            inner: RwLock::new(runtime),
+            write_permits: None,
+            max_concurrent_writes: None,
+            queued_writers: AtomicUsize::new(0),
+            write_generation: Mutex::new(0),
+            writes_released: Condvar::new(),
+        }
+    }
+
+    /// Bound the number of writers that may hold or wait for the write lock.
+    ///
+    /// Returns `BadRequest` for a limit of zero, which would refuse every writer.
+    pub fn with_max_concurrent_writes(mut self, limit: usize) -> Result<Self, McpError> {
+        if limit == 0 {
+            return Err(McpError::BadRequest {
+                message: "max_concurrent_writes must be >= 1".to_string(),
+            });
+        }
+        self.write_permits = Some(Semaphore::new(limit));
+        self.max_concurrent_writes = Some(limit);
+        Ok(self)
+    }
+
+    /// Configured write limit (`None` = unlimited)
+    pub fn max_concurrent_writes(&self) -> Option<usize> {
+        self.max_concurrent_writes
+    }
+
+    /// Approximate number of writers holding or waiting for the write lock
+    pub fn write_queue_depth(&self) -> usize {
+        self.queued_writers.load(Ordering::Acquire)
+    }
+
+    /// Current write-contention level, derived from the write queue depth
+    pub fn load(&self) -> LoadLevel {
+        LoadLevel::from_queue_depth(self.write_queue_depth(), self.max_concurrent_writes)
+    }
+
+    /// Acquire shared read access (never throttled)
+    pub fn read(&self) -> RwLockReadGuard<'_, StubRuntime> {
+        self.inner.read()
+    }
+
+    /// Acquire exclusive write access.
+    ///
+    /// Returns `McpError::RateLimited` if the write queue is saturated.
+    pub fn write(&self) -> Result<SharedWriteGuard<'_>, McpError> {
+        let permit = match &self.write_permits {
+            Some(semaphore) => {
+                Some(semaphore.try_acquire().map_err(|_| McpError::RateLimited {
+                    limit: self.max_concurrent_writes.unwrap_or_default(),
+                })?)
+            }
+            None => None,
+        };
+
+        let queued = QueueSlot::enter(&self.queued_writers);
+        Ok(SharedWriteGuard {
+            guard: Some(self.inner.write()),
+            shared: self,
+            _queued: queued,
+            _permit: permit,
+        })
+    }
+
+    /// Lineage entries after `since`, waiting up to `timeout` for at least one.
+    ///
+    /// Returns as soon as entries exist; otherwise blocks until a writer
+    /// releases the lock with new entries or the timeout passes, in which
+    /// case `entries` is empty and the cursor is unchanged. The timeout runs
+    /// on the runtime's [`Clock`](super::clock::Clock), so a mock clock
+    /// ends the wait only once it is advanced.
+    pub fn tail_lineage(&self, since: Option<u64>, timeout: Duration) -> LineageTail {
+        let clock = self.read().clock();
+        let timeout_ms = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+        let deadline = clock.tick().saturating_add(timeout_ms);
+        loop {
+            let generation = *self.write_generation.lock();
+            let tail = self.read().lineage_since(since);
+            if !tail.entries.is_empty() {
+                return tail;
+            }
+            let mut current = self.write_generation.lock();
+            while *current == generation {
+                let remaining = deadline.saturating_sub(clock.tick());
+                if remaining == 0 {
+                    drop(current);
+                    return self.read().lineage_since(since);
+                }
+                self.writes_released
+                    .wait_for(&mut current, Duration::from_millis(remaining));
+            }
+        }
+    }
+}
+
+impl Deref for SharedWriteGuard<'_> {
+    type Target = StubRuntime;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.as_ref().expect("guard held until drop")
+    }
+}
+
+impl DerefMut for SharedWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.as_mut().expect("guard held until drop")
+    }
+}
+
+impl Drop for SharedWriteGuard<'_> {
+    fn drop(&mut self) {
+        // Release the lock before waking tailers so they can read immediately
+        self.guard.take();
+        *self.shared.write_generation.lock() += 1;
+        self.shared.writes_released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn unlimited_writes_never_rate_limited() {
+        let shared = SharedStubRuntime::default();
+        assert!(shared.max_concurrent_writes().is_none());
+
+        for _ in 0..10 {
+            let mut guard = shared.write().expect("unlimited writer");
+            guard.create_node(0.5, 1.0);
+        }
+        assert_eq!(shared.read().governor_status().node_count, 10);
+    }
+
+    #[test]
+    fn zero_write_limit_is_rejected() {
+        assert!(matches!(
+            SharedStubRuntime::default().with_max_concurrent_writes(0),
+            Err(McpError::BadRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn reads_are_not_throttled() {
+        let shared = SharedStubRuntime::default()
+            .with_max_concurrent_writes(1)
+            .unwrap();
+
+        // Concurrent readers do not consume write permits
+        let readers: Vec<_> = (0..8).map(|_| shared.read()).collect();
+        assert_eq!(readers.len(), 8);
+        drop(readers);
+
+        assert!(shared.write().is_ok());
+    }
+
+    #[test]
+    fn saturated_write_queue_returns_rate_limited() {
+        let shared = SharedStubRuntime::default()
+            .with_max_concurrent_writes(1)
+            .unwrap();
+        let _held = shared.write().expect("first writer");
+
+        match shared.write() {
+            Err(McpError::RateLimited { limit }) => assert_eq!(limit, 1),
+            Err(other) => panic!("expected RateLimited, got {:?}", other),
+            Ok(_) => panic!("expected RateLimited, got a write guard"),
+        };
+    }
+
+    #[test]
+    fn excess_writers_are_rate_limited_under_contention() {
+        const THREADS: usize = 32;
+        const LIMIT: usize = 4;
+
+        let shared = Arc::new(
+            SharedStubRuntime::default()
+                .with_max_concurrent_writes(LIMIT)
+                .unwrap(),
+        );
+
+        // Hold the lock (and one permit) so admitted writers queue behind us
+        let held = shared.write().expect("holder");
+
+        let (tx, rx) = mpsc::channel();
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let outcome = match shared.write() {
+                        Ok(mut guard) => {
+                            guard.create_node(0.5, 1.0);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    };
+                    tx.send(outcome).unwrap();
+                })
+            })
+            .collect();
+        drop(tx);
+
+        // Every writer beyond the limit must fail fast while the lock is held
+        let expected_limited = THREADS - (LIMIT - 1);
+        for _ in 0..expected_limited {
+            let outcome = rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("rate-limited writers must not queue");
+            assert!(matches!(outcome, Err(McpError::RateLimited { .. })));
+        }
+
+        drop(held);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let admitted: Vec<_> = rx.iter().collect();
+        assert_eq!(admitted.len(), LIMIT - 1);
+        assert!(admitted.iter().all(|o| o.is_ok()));
+        assert_eq!(shared.read().governor_status().node_count, LIMIT - 1);
+    }
+
+    #[test]
+    fn tail_lineage_unblocks_on_concurrent_append() {
+        let shared = Arc::new(SharedStubRuntime::default());
+        shared.write().unwrap().create_node(0.5, 1.0);
+        let cursor = shared.read().lineage_since(None).cursor;
+        assert_eq!(cursor, Some(0));
+
+        let tailer = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || shared.tail_lineage(cursor, Duration::from_secs(10)))
+        };
+        thread::sleep(Duration::from_millis(20));
+        shared.write().unwrap().create_node(0.5, 1.0);
+
+        let tail = tailer.join().unwrap();
+        assert_eq!(tail.entries.len(), 1);
+        assert_eq!(tail.entries[0].sequence, 1);
+        assert_eq!(tail.cursor, Some(1));
+    }
+
+    #[test]
+    fn tail_lineage_timeout_runs_on_the_runtime_clock() {
+        let clock = Arc::new(crate::substrate::clock::MockClock::new(0));
+        let shared = Arc::new(SharedStubRuntime::default());
+        shared.write().unwrap().set_clock(clock.clone());
+
+        let tailer = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || shared.tail_lineage(None, Duration::from_millis(20)))
+        };
+        // Well past the timeout in real time, but the mock has not moved
+        thread::sleep(Duration::from_millis(100));
+        assert!(!tailer.is_finished());
+
+        clock.advance(20);
+        let tail = tailer.join().unwrap();
+        assert!(tail.entries.is_empty());
+    }
+
+    #[test]
+    fn tail_lineage_returns_empty_on_timeout() {
+        let shared = SharedStubRuntime::default();
+        shared.write().unwrap().create_node(0.5, 1.0);
+
+        let started = Instant::now();
+        let tail = shared.tail_lineage(Some(0), Duration::from_millis(30));
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert!(tail.entries.is_empty());
+        assert_eq!(tail.cursor, Some(0));
+        // Existing entries are returned without waiting
+        assert_eq!(
+            shared
+                .tail_lineage(None, Duration::from_secs(10))
+                .entries
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn load_classifies_queue_depth() {
+        assert_eq!(LoadLevel::from_queue_depth(0, Some(4)), LoadLevel::Ok);
+        assert_eq!(LoadLevel::from_queue_depth(1, Some(4)), LoadLevel::Ok);
+        assert_eq!(LoadLevel::from_queue_depth(2, Some(4)), LoadLevel::Elevated);
+        assert_eq!(
+            LoadLevel::from_queue_depth(4, Some(4)),
+            LoadLevel::Saturated
+        );
+        assert_eq!(
+            LoadLevel::from_queue_depth(1, Some(1)),
+            LoadLevel::Saturated
+        );
+        assert_eq!(
+            LoadLevel::from_queue_depth(UNLIMITED_SATURATION_DEPTH, None),
+            LoadLevel::Saturated
+        );
+    }
+
+    #[test]
+    fn load_rises_under_contention_and_recovers() {
+        const THREADS: usize = 32;
+        const LIMIT: usize = 4;
+
+        let shared = Arc::new(
+            SharedStubRuntime::default()
+                .with_max_concurrent_writes(LIMIT)
+                .unwrap(),
+        );
+        assert_eq!(shared.load(), LoadLevel::Ok);
+
+        let held = shared.write().expect("holder");
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    if let Ok(mut guard) = shared.write() {
+                        guard.create_node(0.5, 1.0);
+                    }
+                })
+            })
+            .collect();
+
+        // Admitted writers park behind the held lock, filling the queue
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while shared.write_queue_depth() < LIMIT {
+            assert!(std::time::Instant::now() < deadline, "writers never queued");
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(shared.load() > LoadLevel::Ok);
+        assert_eq!(shared.load(), LoadLevel::Saturated);
+
+        drop(held);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(shared.write_queue_depth(), 0);
+        assert_eq!(shared.load(), LoadLevel::Ok);
+    }
+}
@@ -19,19 +19,92 @@ use std::fmt;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum McpError {
     /// Node not found by ID
-    NodeNotFound { id: u64 },
+    NodeNotFound {
+        /// Requested node ID
+        id: u64,
+    },
     /// Edge not found by ID
-    EdgeNotFound { id: u64 },
+    EdgeNotFound {
+        /// Requested edge ID
+        id: u64,
+    },
     /// ESV validation failed
-    EsvValidationFailed { reason: String },
+    EsvValidationFailed {
+        /// Sanitized failure reason
+        reason: String,
+    },
     /// Drift exceeded threshold
-    DriftExceeded { drift: f64, threshold: f64 },
+    DriftExceeded {
+        /// Observed drift
+        drift: f64,
+        /// Configured threshold
+        threshold: f64,
+    },
     /// Lineage integrity violation
-    LineageCorruption { details: String },
+    LineageCorruption {
+        /// Sanitized corruption details
+        details: String,
+    },
     /// Generic substrate error
-    SubstrateError { message: String },
+    SubstrateError {
+        /// Sanitized error message
+        message: String,
+    },
     /// Invalid request parameters
-    BadRequest { message: String },
+    BadRequest {
+        /// Human-readable validation message
+        message: String,
+    },
+    /// Caller is not authorized for a privileged operation
+    Forbidden {
+        /// Operation that was refused
+        operation: String,
+    },
+    /// Client-supplied deadline passed before the operation finished
+    DeadlineExceeded {
+        /// Operation that was aborted
+        operation: String,
+        /// Steps fully applied before the abort
+        completed_steps: u64,
+    },
+    /// Write concurrency limit saturated
+    RateLimited {
+        /// Configured maximum concurrent writers
+        limit: usize,
+    },
+    /// A single substrate step would exceed its work budget
+    Stalled {
+        /// Operation whose step was refused
+        operation: String,
+        /// Work units the step needed
+        work: u64,
+        /// Configured per-step budget
+        budget: u64,
+    },
+    /// Mutation refused while the runtime is quarantined (reads still work)
+    Quarantined {
+        /// Operation that was refused
+        operation: String,
+    },
+    /// Call vetoed by the configured external policy decider
+    PolicyDenied {
+        /// Operation that was refused
+        operation: String,
+        /// Decider-supplied reason
+        reason: String,
+    },
+    /// Operation would exceed a configured size limit; nothing was written
+    CapacityExceeded {
+        /// What hit the limit (e.g. `lineage.export`)
+        resource: String,
+        /// Configured limit in bytes
+        limit: u64,
+    },
+    /// Mutation refused because the node is locked (`node.lock`)
+    NodeLocked {
+        /// Locked node
+        id: u64,
+    },
 }
 
 impl fmt::Display for McpError {
@@ -48,6 +121,41 @@ impl fmt::Display for McpError {
             McpError::LineageCorruption { details } => write!(f, "Lineage corruption: {}", details),
             McpError::SubstrateError { message } => write!(f, "Substrate error: {}", message),
             McpError::BadRequest { message } => write!(f, "Bad request: {}", message),
+            McpError::Forbidden { operation } => write!(f, "Forbidden: {}", operation),
+            McpError::DeadlineExceeded {
+                operation,
+                completed_steps,
+            } => write!(
+                f,
+                "Deadline exceeded: {} aborted after {} steps",
+                operation, completed_steps
+            ),
+            McpError::RateLimited { limit } => {
+                write!(f, "Rate limited: write queue saturated (limit {})", limit)
+            }
+            McpError::Stalled {
+                operation,
+                work,
+                budget,
+            } => write!(
+                f,
+                "Stalled: {} step needs {} work units (budget {})",
+                operation, work, budget
+            ),
+            McpError::Quarantined { operation } => {
+                write!(
+                    f,
+                    "Quarantined: {} is blocked until quarantine clears",
+                    operation
+                )
+            }
+            McpError::PolicyDenied { operation, reason } => {
+                write!(f, "Policy denied: {}: {}", operation, reason)
+            }
+            McpError::CapacityExceeded { resource, limit } => {
+                write!(f, "Capacity exceeded: {} over {} bytes", resource, limit)
+            }
+            McpError::NodeLocked { id } => write!(f, "Node locked: N{}", id),
         }
     }
 }
@@ -65,6 +173,14 @@ impl McpError {
             McpError::LineageCorruption { .. } => 3000,
             McpError::SubstrateError { .. } => 5000,
             McpError::BadRequest { .. } => 4000,
+            McpError::Forbidden { .. } => 4003,
+            McpError::DeadlineExceeded { .. } => 4008,
+            McpError::RateLimited { .. } => 4029,
+            McpError::Stalled { .. } => 5001,
+            McpError::Quarantined { .. } => 2001,
+            McpError::PolicyDenied { .. } => 4051,
+            McpError::CapacityExceeded { .. } => 4013,
+            McpError::NodeLocked { .. } => 4023,
         }
     }
 
@@ -78,6 +194,14 @@ impl McpError {
             McpError::LineageCorruption { .. } => "lineage_corruption",
             McpError::SubstrateError { .. } => "substrate_error",
             McpError::BadRequest { .. } => "bad_request",
+            McpError::Forbidden { .. } => "forbidden",
+            McpError::DeadlineExceeded { .. } => "deadline_exceeded",
+            McpError::RateLimited { .. } => "rate_limited",
+            McpError::Stalled { .. } => "stalled",
+            McpError::Quarantined { .. } => "quarantined",
+            McpError::PolicyDenied { .. } => "policy_denied",
+            McpError::CapacityExceeded { .. } => "capacity_exceeded",
+            McpError::NodeLocked { .. } => "node_locked",
         }
     }
 
@@ -85,6 +209,54 @@ impl McpError {
     pub fn error_code(&self) -> i32 {
         self.code() as i32
     }
+
+    /// Outcome class for responses and audit records
+    pub fn outcome(&self) -> AuditOutcome {
+        AuditOutcome::from_error_code(self.code() as i64)
+    }
+}
+
+/// Coarse result class shared by response `_meta.outcome` and audit records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Request completed
+    Success,
+    /// Request was valid but could not be carried out (missing target,
+    /// substrate or lineage fault)
+    Failure,
+    /// Request was refused before executing (malformed, unauthorized,
+    /// policy-denied, over capacity, locked, rate-limited, quarantined, or
+    /// failed ESV validation)
+    Rejected,
+    /// Request ran out of time or work budget
+    Timeout,
+}
+
+impl AuditOutcome {
+    /// Classify a tool error code (see `McpError::code`) or JSON-RPC error code.
+    ///
+    /// JSON-RPC protocol errors (`-32700..=-32600`) and `-32002` (server not
+    /// initialized) are rejections; unknown codes count as failures.
+    pub fn from_error_code(code: i64) -> Self {
+        match code {
+            1000 | 2001 | 4000 | 4003 | 4013 | 4023 | 4029 | 4051 | -32002 | -32700..=-32600 => {
+                AuditOutcome::Rejected
+            }
+            4008 | 5001 => AuditOutcome::Timeout,
+            _ => AuditOutcome::Failure,
+        }
+    }
+
+    /// Classify a handler result: tool errors carry `{"error": {"code": ...}}`
+    pub fn of_result(result: &serde_json::Value) -> Self {
+        match result.get("error") {
+            Some(error) => {
+                Self::from_error_code(error.get("code").and_then(|c| c.as_i64()).unwrap_or(0))
+            }
+            None => AuditOutcome::Success,
+        }
+    }
 }
 
 // ============================================================================
@@ -104,6 +276,9 @@ pub struct McpNodeState {
     pub esv_valid: bool,
     /// Stability indicator [0.0, 1.0]
     pub stability: f64,
+    /// Whether the node is locked against mutation (`node.lock`, protocol 1.1)
+    #[serde(default)]
+    pub locked: bool,
 }
 
 /// Sanitized edge state for MCP responses
@@ -136,8 +311,27 @@ pub struct McpGovernorStatus {
     pub healthy: bool,
 }
 
-/// Sanitized lineage entry for MCP responses
+/// Sanitized ESV audit result for MCP responses
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpEsvAudit {
+    /// Audited node ID
+    pub node_id: u64,
+    /// ESV validity flag
+    pub valid: bool,
+    /// Compliance status label (e.g. "compliant")
+    pub compliance_status: String,
+    /// Named constraint categories the node violates (e.g. "belief_bounds");
+    /// omitted when none are violated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violated_constraints: Vec<String>,
+    /// Coarse distance to the nearest constraint boundary (negative when
+    /// violated, rounded to two decimals); no raw ESV components
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margin: Option<f64>,
+}
+
+/// Sanitized lineage entry for MCP responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct McpLineageEntry {
     /// Entry sequence number
     pub sequence: u64,
@@ -147,4 +341,10 @@ pub struct McpLineageEntry {
     pub checksum: String,
     /// Tick when recorded
     pub tick: u64,
+    /// Short MCP operation label (e.g. "node.create"); no IDs or topology
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Wall-clock time when recorded (Unix milliseconds); absent in deterministic mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_unix_ms: Option<u64>,
 }
@@ -19,15 +19,20 @@ pub mod version;
 
 // Re-export protocol types
 pub use protocol::{
-    BindEdgeParams, CreateNodeParams, ExportLineageParams, MutateNodeParams, PropagateEdgeParams,
-    QueryNodeParams, RpcError, RpcRequest, RpcResponse, ToolInfo, ToolList,
+    BindEdgeParams, CreateNodeParams, ExportLineageParams, MutateNodeParams, PingResult,
+    PropagateEdgeParams, QueryNodeParams, ResponseMeta, RpcError, RpcRequest, RpcResponse,
+    ToolInfo, ToolList,
 };
 
 // Re-export MCP types
-pub use mcp::{McpEdgeState, McpError, McpGovernorStatus, McpLineageEntry, McpNodeState};
+pub use mcp::{
+    AuditOutcome, McpEdgeState, McpError, McpEsvAudit, McpGovernorStatus, McpLineageEntry,
+    McpNodeState,
+};
 
 // Re-export version types
 pub use version::{
-    CompatibilityStatus, Deprecation, ProtocolVersion, MIN_SUPPORTED_MAJOR, PROTOCOL_MAJOR,
-    PROTOCOL_MINOR, PROTOCOL_PATCH, PROTOCOL_VERSION,
+    deprecation_for, CompatibilityStatus, DeprecatedItem, Deprecation, ProtocolVersion,
+    DEPRECATIONS, MIN_SUPPORTED_MAJOR, PROTOCOL_MAJOR, PROTOCOL_MINOR, PROTOCOL_PATCH,
+    PROTOCOL_VERSION,
 };
@@ -5,6 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::mcp::AuditOutcome;
+use super::version::PROTOCOL_VERSION;
+
 // ============================================================================
 // JSON-RPC 2.0 Protocol Types
 // ============================================================================
@@ -37,6 +40,65 @@ pub struct RpcResponse {
     pub error: Option<RpcError>,
     /// Request ID
     pub id: serde_json::Value,
+    /// Server metadata (protocol version); omitted if the client opted out
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+}
+
+/// Per-response server metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMeta {
+    /// Server protocol version (`PROTOCOL_VERSION`)
+    pub protocol_version: String,
+    /// Result class (`AuditOutcome`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<AuditOutcome>,
+    /// Non-fatal notices about this response (sanitizer findings,
+    /// deprecations); only sent to clients that opted in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ResponseMeta {
+    /// Metadata for this build
+    pub fn current() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            outcome: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Metadata for this build, classifying the response
+    pub fn with_outcome(outcome: AuditOutcome) -> Self {
+        Self {
+            outcome: Some(outcome),
+            ..Self::current()
+        }
+    }
+}
+
+/// Result of the `ping` method (transport liveness check)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingResult {
+    /// Always `true`
+    pub pong: bool,
+    /// Server wall-clock time, seconds since the Unix epoch
+    pub server_time_unix: u64,
+}
+
+impl PingResult {
+    /// Pong stamped with the current wall-clock time
+    pub fn now() -> Self {
+        let server_time_unix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Self {
+            pong: true,
+            server_time_unix,
+        }
+    }
 }
 
 /// JSON-RPC 2.0 Error
@@ -49,10 +111,14 @@ pub struct RpcError {
 }
 
 impl RpcResponse {
-    /// Create a success response
+    /// Create a success response.
+    ///
+    /// `_meta.outcome` is derived from the result, so tool errors reported
+    /// inside `result` are still classified.
     pub fn success(id: serde_json::Value, value: serde_json::Value) -> Self {
         Self {
             jsonrpc: "2.0".into(),
+            meta: Some(ResponseMeta::with_outcome(AuditOutcome::of_result(&value))),
             result: Some(value),
             error: None,
             id,
@@ -69,8 +135,25 @@ impl RpcResponse {
                 message: msg.into(),
             }),
             id,
+            meta: Some(ResponseMeta::with_outcome(AuditOutcome::from_error_code(
+                code.into(),
+            ))),
         }
     }
+
+    /// Attach non-fatal notices as `_meta.warnings` (no-op without `_meta`)
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        if let Some(meta) = self.meta.as_mut() {
+            meta.warnings = warnings;
+        }
+        self
+    }
+
+    /// Drop `_meta` (for clients that opted out during `initialize`)
+    pub fn without_meta(mut self) -> Self {
+        self.meta = None;
+        self
+    }
 }
 
 // ============================================================================
@@ -150,4 +233,7 @@ pub struct PropagateEdgeParams {
 pub struct ExportLineageParams {
     /// Export file path
     pub path: String,
+    /// Gzip the output (requires the `lineage_gzip` feature)
+    #[serde(default)]
+    pub compress: Option<bool>,
 }
@@ -17,13 +17,13 @@
 use serde::{Deserialize, Serialize};
 
 /// Current protocol version
-pub const PROTOCOL_VERSION: &str = "1.0.0";
+pub const PROTOCOL_VERSION: &str = "1.1.0";
 
 /// Protocol major version (for compatibility checks)
 pub const PROTOCOL_MAJOR: u32 = 1;
 
 /// Protocol minor version
-pub const PROTOCOL_MINOR: u32 = 0;
+pub const PROTOCOL_MINOR: u32 = 1;
 
 /// Protocol patch version
 pub const PROTOCOL_PATCH: u32 = 0;
@@ -100,9 +100,15 @@ pub enum CompatibilityStatus {
     /// Forward compatible (same major, higher minor - client is newer)
     ForwardCompatible,
     /// Deprecated (older major, still supported)
-    Deprecated { supported_until: &'static str },
+    Deprecated {
+        /// Version until which the client remains supported
+        supported_until: &'static str,
+    },
     /// Incompatible (outside support window)
-    Incompatible { reason: String },
+    Incompatible {
+        /// Why the version is rejected
+        reason: String,
+    },
 }
 
 impl ProtocolVersion {
@@ -162,6 +168,50 @@ impl Deprecation {
     }
 }
 
+/// Entry in the protocol deprecation schedule (`server.deprecations`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DeprecatedItem {
+    /// Deprecated method, tool, or field name
+    pub item: &'static str,
+    /// Protocol version that deprecated it
+    pub deprecated_in: &'static str,
+    /// Protocol version that will remove it
+    pub removed_in: &'static str,
+    /// What clients should use instead
+    pub replacement: &'static str,
+}
+
+/// Central deprecation registry; the single source for the schedule and
+/// for per-item deprecation notes
+pub const DEPRECATIONS: &[DeprecatedItem] = &[
+    DeprecatedItem {
+        item: "tools.list",
+        deprecated_in: "1.0.0",
+        removed_in: "2.0.0",
+        replacement: "tools/list",
+    },
+    DeprecatedItem {
+        item: "governor.status",
+        deprecated_in: "1.0.0",
+        removed_in: "2.0.0",
+        replacement: "governance.status",
+    },
+];
+
+/// Deprecation note for `item`, if it is in the registry
+pub fn deprecation_for(item: &str) -> Option<Deprecation> {
+    DEPRECATIONS
+        .iter()
+        .find(|entry| entry.item == item)
+        .map(|entry| {
+            Deprecation::new(
+                entry.deprecated_in,
+                entry.removed_in,
+                &format!("Use {} instead", entry.replacement),
+            )
+        })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -225,6 +275,26 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn registry_lists_known_aliases() {
+        for (alias, replacement) in [
+            ("tools.list", "tools/list"),
+            ("governor.status", "governance.status"),
+        ] {
+            let entry = DEPRECATIONS
+                .iter()
+                .find(|entry| entry.item == alias)
+                .unwrap_or_else(|| panic!("{} missing from registry", alias));
+            assert_eq!(entry.replacement, replacement);
+            assert!(!entry.deprecated_in.is_empty() && !entry.removed_in.is_empty());
+        }
+        assert!(deprecation_for("governor.status")
+            .unwrap()
+            .message
+            .contains("governance.status"));
+        assert!(deprecation_for("governance.status").is_none());
+    }
+
     #[test]
     fn deprecation_marker() {
         let d = Deprecation::new("1.0.0", "2.0.0", "Use new_field instead");
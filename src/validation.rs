@@ -5,6 +5,8 @@
 //! not domain-specific physics or ethics (handled by substrate).
 
 use crate::types::McpError;
+use serde_json::Value;
+use std::path::{Component, Path};
 
 // ============================================================================
 // Validation Constants
@@ -25,6 +27,12 @@ pub const MIN_ENERGY: f64 = 0.0;
 /// Maximum weight for edges
 pub const MAX_WEIGHT: f64 = 1e6;
 
+/// JSON Schema `pattern` used for numeric-string IDs (the only pattern understood)
+pub const NUMERIC_ID_PATTERN: &str = "^[0-9]+$";
+
+/// Maximum serialized size of a node's caller metadata in bytes
+pub const MAX_NODE_METADATA_BYTES: usize = 16 * 1024;
+
 /// Maximum JSON payload size in bytes
 /// Reserved for future payload size validation at MCP boundary.
 #[allow(dead_code)]
@@ -48,7 +56,10 @@ pub fn validate_belief(belief: f64) -> Result<f64, McpError> {
     }
     if !(MIN_BELIEF..=MAX_BELIEF).contains(&belief) {
         return Err(McpError::BadRequest {
-            message: format!("belief {} out of valid range [{}, {}]", belief, MIN_BELIEF, MAX_BELIEF),
+            message: format!(
+                "belief {} out of valid range [{}, {}]",
+                belief, MIN_BELIEF, MAX_BELIEF
+            ),
         });
     }
     Ok(belief)
@@ -56,6 +67,11 @@ pub fn validate_belief(belief: f64) -> Result<f64, McpError> {
 
 /// Validate energy value is non-negative and within practical limits.
 pub fn validate_energy(energy: f64) -> Result<f64, McpError> {
+    validate_energy_max(energy, MAX_ENERGY)
+}
+
+/// Validate energy against a caller-chosen upper bound (e.g. `max_node_energy`).
+pub fn validate_energy_max(energy: f64, max: f64) -> Result<f64, McpError> {
     if energy.is_nan() {
         return Err(McpError::BadRequest {
             message: "energy cannot be NaN".to_string(),
@@ -71,9 +87,9 @@ pub fn validate_energy(energy: f64) -> Result<f64, McpError> {
             message: format!("energy {} cannot be negative", energy),
         });
     }
-    if energy > MAX_ENERGY {
+    if energy > max {
         return Err(McpError::BadRequest {
-            message: format!("energy {} exceeds maximum {}", energy, MAX_ENERGY),
+            message: format!("energy {} exceeds maximum {}", energy, max),
         });
     }
     Ok(energy)
@@ -106,6 +122,25 @@ pub fn validate_node_id(node_id: &str) -> Result<u64, McpError> {
     })
 }
 
+/// Validate a caller-supplied file path.
+///
+/// Only relative paths without `..` components are accepted, so a tool can
+/// never reach outside the server's working directory.
+pub fn validate_relative_path(path: &str) -> Result<&Path, McpError> {
+    let candidate = Path::new(path);
+    let safe = !path.is_empty()
+        && !path.contains('\0')
+        && candidate
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !safe {
+        return Err(McpError::BadRequest {
+            message: "path must be relative and must not contain '..'".to_string(),
+        });
+    }
+    Ok(candidate)
+}
+
 /// Validate payload size is within limits.
 /// Reserved for future payload size validation at MCP boundary.
 #[allow(dead_code)]
@@ -122,6 +157,197 @@ pub fn validate_payload_size(payload: &[u8]) -> Result<(), McpError> {
     Ok(())
 }
 
+/// Validate caller metadata about to be stored on a node.
+///
+/// It must fit in [`MAX_NODE_METADATA_BYTES`] once serialized, and no key or
+/// string value at any depth may contain a sanitizer forbidden pattern
+/// (stored text is echoed back, so it is refused up front instead).
+pub fn validate_node_metadata(metadata: &Value) -> Result<(), McpError> {
+    let size = metadata.to_string().len();
+    if size > MAX_NODE_METADATA_BYTES {
+        return Err(McpError::BadRequest {
+            message: format!(
+                "metadata size {} exceeds maximum {} bytes",
+                size, MAX_NODE_METADATA_BYTES
+            ),
+        });
+    }
+    if contains_forbidden_text(metadata) {
+        return Err(McpError::BadRequest {
+            message: "metadata contains a forbidden pattern".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn contains_forbidden_text(value: &Value) -> bool {
+    match value {
+        Value::Object(fields) => fields.iter().any(|(key, field)| {
+            crate::sanitizer::forbidden_pattern(key).is_some() || contains_forbidden_text(field)
+        }),
+        Value::Array(items) => items.iter().any(contains_forbidden_text),
+        Value::String(text) => crate::sanitizer::forbidden_pattern(text).is_some(),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Schema-Driven Argument Validation
+// ============================================================================
+
+/// Validate tool arguments against an advertised `inputSchema`.
+///
+/// Supports the subset of JSON Schema used by the tool surface: `required`,
+/// `type` (number, integer, string, boolean, object, array), `minimum`,
+/// `maximum`, and the numeric-ID `pattern`. Messages name the public argument
+/// and its expectation (e.g. "belief is required and must be a number in [0, 1]")
+/// and never echo Rust type or field names.
+pub fn validate_args(args: &Value, schema: &Value) -> Result<(), McpError> {
+    let Some(args) = args.as_object() else {
+        return Err(McpError::BadRequest {
+            message: "arguments must be an object".to_string(),
+        });
+    };
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+
+    if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+        for name in required.iter().filter_map(|n| n.as_str()) {
+            if args.get(name).is_none_or(Value::is_null) {
+                let expectation = properties
+                    .and_then(|p| p.get(name))
+                    .map(describe_expectation)
+                    .unwrap_or_else(|| "present".to_string());
+                return Err(McpError::BadRequest {
+                    message: format!("{} is required and must be {}", name, expectation),
+                });
+            }
+        }
+    }
+
+    if let Some(properties) = properties {
+        for (name, property) in properties {
+            let invalid = args
+                .get(name)
+                .is_some_and(|value| !value.is_null() && !matches_property(value, property));
+            if invalid {
+                return Err(McpError::BadRequest {
+                    message: format!("{} must be {}", name, describe_expectation(property)),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Most unexpected argument names echoed back by `reject_unknown_args`
+const MAX_REPORTED_UNKNOWN_ARGS: usize = 8;
+
+/// Longest unexpected argument name echoed back (longer names are truncated)
+const MAX_REPORTED_ARG_NAME_LEN: usize = 32;
+
+/// Reject arguments the tool's `inputSchema` does not declare (`strict_params`).
+///
+/// The error lists the offending names, sorted, with characters outside
+/// `[A-Za-z0-9_.-]` replaced by `?` and each name and the list capped, so
+/// caller-supplied keys cannot smuggle arbitrary text into responses.
+pub fn reject_unknown_args(args: &Value, schema: &Value) -> Result<(), McpError> {
+    let Some(args) = args.as_object() else {
+        return Ok(());
+    };
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+    let mut unknown: Vec<String> = args
+        .keys()
+        .filter(|name| properties.is_none_or(|p| !p.contains_key(name.as_str())))
+        .map(|name| sanitize_arg_name(name))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    unknown.sort();
+    let total = unknown.len();
+    unknown.truncate(MAX_REPORTED_UNKNOWN_ARGS);
+    let mut listed = unknown.join(", ");
+    if total > MAX_REPORTED_UNKNOWN_ARGS {
+        listed.push_str(&format!(" (+{} more)", total - MAX_REPORTED_UNKNOWN_ARGS));
+    }
+    Err(McpError::BadRequest {
+        message: format!("unexpected arguments: {}", listed),
+    })
+}
+
+fn sanitize_arg_name(name: &str) -> String {
+    name.chars()
+        .take(MAX_REPORTED_ARG_NAME_LEN)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+fn matches_property(value: &Value, property: &Value) -> bool {
+    let type_ok = match property.get("type").and_then(|t| t.as_str()) {
+        Some("number") => value.as_f64().is_some(),
+        Some("integer") => value.as_i64().is_some() || value.as_u64().is_some(),
+        Some("string") => value.is_string(),
+        Some("boolean") => value.is_boolean(),
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        _ => true,
+    };
+    if !type_ok {
+        return false;
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = property.get("minimum").and_then(|m| m.as_f64()) {
+            if n < min {
+                return false;
+            }
+        }
+        if let Some(max) = property.get("maximum").and_then(|m| m.as_f64()) {
+            if n > max {
+                return false;
+            }
+        }
+    }
+
+    if property.get("pattern").and_then(|p| p.as_str()) == Some(NUMERIC_ID_PATTERN) {
+        return value.as_str().is_some_and(|s| validate_node_id(s).is_ok());
+    }
+
+    true
+}
+
+fn describe_expectation(property: &Value) -> String {
+    let min = property.get("minimum").and_then(|m| m.as_f64());
+    let max = property.get("maximum").and_then(|m| m.as_f64());
+    let noun = match property.get("type").and_then(|t| t.as_str()) {
+        Some("number") => "a number",
+        Some("integer") => "an integer",
+        Some("string")
+            if property.get("pattern").and_then(|p| p.as_str()) == Some(NUMERIC_ID_PATTERN) =>
+        {
+            return "a numeric string".to_string();
+        }
+        Some("string") => return "a string".to_string(),
+        Some("boolean") => return "a boolean".to_string(),
+        Some("object") => return "an object".to_string(),
+        Some("array") => return "an array".to_string(),
+        _ => return "valid".to_string(),
+    };
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{} in [{}, {}]", noun, min, max),
+        (Some(min), None) => format!("{} >= {}", noun, min),
+        (None, Some(max)) => format!("{} <= {}", noun, max),
+        (None, None) => noun.to_string(),
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -189,6 +415,91 @@ mod tests {
         assert!(validate_node_id("1.5").is_err());
     }
 
+    fn create_schema() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "belief": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                "energy": { "type": "number", "minimum": 0.0 },
+                "node_id": { "type": "string", "pattern": NUMERIC_ID_PATTERN }
+            },
+            "required": ["belief", "energy"]
+        })
+    }
+
+    fn message(err: McpError) -> String {
+        match err {
+            McpError::BadRequest { message } => message,
+            other => panic!("expected BadRequest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_args_valid() {
+        let args = serde_json::json!({"belief": 0.5, "energy": 10, "node_id": "3"});
+        assert!(validate_args(&args, &create_schema()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_args_missing_field() {
+        let args = serde_json::json!({"energy": 1.0});
+        let err = validate_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(
+            message(err),
+            "belief is required and must be a number in [0, 1]"
+        );
+    }
+
+    #[test]
+    fn test_validate_args_wrong_type() {
+        let args = serde_json::json!({"belief": "high", "energy": 1.0});
+        let err = validate_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(message(err), "belief must be a number in [0, 1]");
+
+        let args = serde_json::json!({"belief": 0.5, "energy": 1.0, "node_id": 7});
+        let err = validate_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(message(err), "node_id must be a numeric string");
+    }
+
+    #[test]
+    fn test_validate_args_out_of_range() {
+        let args = serde_json::json!({"belief": 1.5, "energy": 1.0});
+        let err = validate_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(message(err), "belief must be a number in [0, 1]");
+
+        let args = serde_json::json!({"belief": 0.5, "energy": -1.0});
+        let err = validate_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(message(err), "energy must be a number >= 0");
+
+        let args = serde_json::json!({"belief": 0.5, "energy": 1.0, "node_id": "-1"});
+        assert!(validate_args(&args, &create_schema()).is_err());
+    }
+
+    #[test]
+    fn test_validate_args_rejects_non_object() {
+        let err = validate_args(&serde_json::json!([1, 2]), &create_schema()).unwrap_err();
+        assert_eq!(message(err), "arguments must be an object");
+    }
+
+    #[test]
+    fn test_reject_unknown_args_sanitizes_names() {
+        let args = serde_json::json!({"belief": 0.5, "energy": 1.0, "zeta": 1, "a<b>": 2});
+        let err = reject_unknown_args(&args, &create_schema()).unwrap_err();
+        assert_eq!(message(err), "unexpected arguments: a?b?, zeta");
+
+        let args = serde_json::json!({"belief": 0.5, "energy": 1.0});
+        assert!(reject_unknown_args(&args, &create_schema()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_relative_path() {
+        assert!(validate_relative_path("exports/before.json").is_ok());
+        assert!(validate_relative_path("./after.jsonl").is_ok());
+        for bad in ["", "/etc/passwd", "../secret.json", "a/../../b", "a\0b"] {
+            assert!(validate_relative_path(bad).is_err(), "{:?} accepted", bad);
+        }
+    }
+
     #[test]
     fn test_validate_payload_size() {
         let small = vec![0u8; 100];
@@ -27,6 +27,14 @@ const EXPECTED_ERRORS: &[(&str, u32)] = &[
     ("lineage_corruption", 3000),
     ("substrate_error", 5000),
     ("bad_request", 4000),
+    ("forbidden", 4003),
+    ("deadline_exceeded", 4008),
+    ("rate_limited", 4029),
+    ("quarantined", 2001),
+    ("stalled", 5001),
+    ("policy_denied", 4051),
+    ("capacity_exceeded", 4013),
+    ("node_locked", 4023),
 ];
 
 #[test]
@@ -51,6 +59,31 @@ fn all_error_variants_have_codes() {
         McpError::BadRequest {
             message: String::new(),
         },
+        McpError::Forbidden {
+            operation: String::new(),
+        },
+        McpError::DeadlineExceeded {
+            operation: String::new(),
+            completed_steps: 0,
+        },
+        McpError::RateLimited { limit: 0 },
+        McpError::Quarantined {
+            operation: String::new(),
+        },
+        McpError::Stalled {
+            operation: String::new(),
+            work: 0,
+            budget: 0,
+        },
+        McpError::PolicyDenied {
+            operation: String::new(),
+            reason: String::new(),
+        },
+        McpError::CapacityExceeded {
+            resource: String::new(),
+            limit: 0,
+        },
+        McpError::NodeLocked { id: 0 },
     ];
 
     // Every variant must have a non-zero code
@@ -109,6 +142,47 @@ fn error_codes_match_expected() {
             "bad_request",
             4000,
         ),
+        (
+            McpError::Forbidden {
+                operation: String::new(),
+            },
+            "forbidden",
+            4003,
+        ),
+        (
+            McpError::DeadlineExceeded {
+                operation: String::new(),
+                completed_steps: 0,
+            },
+            "deadline_exceeded",
+            4008,
+        ),
+        (McpError::RateLimited { limit: 0 }, "rate_limited", 4029),
+        (
+            McpError::Quarantined {
+                operation: String::new(),
+            },
+            "quarantined",
+            2001,
+        ),
+        (
+            McpError::Stalled {
+                operation: String::new(),
+                work: 0,
+                budget: 0,
+            },
+            "stalled",
+            5001,
+        ),
+        (
+            McpError::CapacityExceeded {
+                resource: String::new(),
+                limit: 0,
+            },
+            "capacity_exceeded",
+            4013,
+        ),
+        (McpError::NodeLocked { id: 0 }, "node_locked", 4023),
     ];
 
     for (err, expected_code_str, expected_code) in errors {
@@ -151,6 +225,31 @@ fn error_codes_are_documented() {
         McpError::BadRequest {
             message: String::new(),
         },
+        McpError::Forbidden {
+            operation: String::new(),
+        },
+        McpError::DeadlineExceeded {
+            operation: String::new(),
+            completed_steps: 0,
+        },
+        McpError::RateLimited { limit: 0 },
+        McpError::Quarantined {
+            operation: String::new(),
+        },
+        McpError::Stalled {
+            operation: String::new(),
+            work: 0,
+            budget: 0,
+        },
+        McpError::PolicyDenied {
+            operation: String::new(),
+            reason: String::new(),
+        },
+        McpError::CapacityExceeded {
+            resource: String::new(),
+            limit: 0,
+        },
+        McpError::NodeLocked { id: 0 },
     ];
 
     for err in &errors {
@@ -169,7 +268,7 @@ fn error_codes_are_documented() {
 #[test]
 fn variant_count_matches_expected() {
     // If someone adds a new variant, this test will fail until EXPECTED_ERRORS is updated
-    let variant_count = 7; // Current number of variants
+    let variant_count = 15; // Current number of variants
     assert_eq!(
         EXPECTED_ERRORS.len(),
         variant_count,
@@ -220,6 +319,42 @@ fn errors_have_meaningful_display() {
             },
             "invalid",
         ),
+        (
+            McpError::Forbidden {
+                operation: "governor.rebaseline".into(),
+            },
+            "governor.rebaseline",
+        ),
+        (
+            McpError::DeadlineExceeded {
+                operation: "edge.propagate".into(),
+                completed_steps: 64,
+            },
+            "64 steps",
+        ),
+        (McpError::RateLimited { limit: 4 }, "limit 4"),
+        (
+            McpError::Quarantined {
+                operation: "node.mutate".into(),
+            },
+            "node.mutate",
+        ),
+        (
+            McpError::Stalled {
+                operation: "edge.propagate".into(),
+                work: 12,
+                budget: 8,
+            },
+            "budget 8",
+        ),
+        (
+            McpError::CapacityExceeded {
+                resource: "lineage.export".into(),
+                limit: 1024,
+            },
+            "1024 bytes",
+        ),
+        (McpError::NodeLocked { id: 9 }, "N9"),
     ];
 
     for (err, expected_substring) in errors {
@@ -256,6 +391,41 @@ fn error_code_conversion_is_consistent() {
     }
 }
 
+#[test]
+fn errors_classify_into_audit_outcomes() {
+    use iter_mcp_server::types::mcp::AuditOutcome;
+    use iter_mcp_server::RpcResponse;
+    use serde_json::json;
+
+    let missing = McpError::NodeNotFound { id: 7 };
+    let invalid = McpError::BadRequest {
+        message: "belief out of range".into(),
+    };
+    assert_eq!(missing.outcome(), AuditOutcome::Failure);
+    assert_eq!(invalid.outcome(), AuditOutcome::Rejected);
+    assert_eq!(
+        McpError::DeadlineExceeded {
+            operation: String::new(),
+            completed_steps: 0,
+        }
+        .outcome(),
+        AuditOutcome::Timeout
+    );
+
+    // Envelopes classify tool errors carried inside `result`
+    let envelope = |err: &McpError| {
+        let result = json!({"error": {"code": err.code(), "message": err.to_string()}});
+        serde_json::to_value(RpcResponse::success(json!(1), result)).unwrap()
+    };
+    assert_eq!(envelope(&missing)["_meta"]["outcome"], "failure");
+    assert_eq!(envelope(&invalid)["_meta"]["outcome"], "rejected");
+    let ok = serde_json::to_value(RpcResponse::success(json!(1), json!({}))).unwrap();
+    assert_eq!(ok["_meta"]["outcome"], "success");
+    let parse =
+        serde_json::to_value(RpcResponse::error(json!(null), -32700, "Parse error")).unwrap();
+    assert_eq!(parse["_meta"]["outcome"], "rejected");
+}
+
 // ============================================================================
 // Error Serialization Invariants
 // ============================================================================
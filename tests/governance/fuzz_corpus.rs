@@ -0,0 +1,186 @@
+//! Fuzz Scenario Corpus
+//!
+//! `mcp_fuzz_scenarios` drives random operation sequences through the stub
+//! runtime and checks state invariants after every step. When a case fails,
+//! proptest's minimized sequence is written to `tests/corpus/` so the
+//! finding survives the run; `replay_corpus` re-runs every saved case.
+//!
+//! Sequences use `EpisodeOp` (the `lineage.replay_episode` format), so a
+//! corpus file is plain JSON: `{ "description": ..., "ops": [...] }`.
+
+use iter_mcp_server::substrate::stub::{EpisodeOp, StubRuntime};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestError, TestRunner};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const CORPUS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus");
+
+/// One saved regression case
+#[derive(Debug, Serialize, Deserialize)]
+struct CorpusCase {
+    description: String,
+    ops: Vec<EpisodeOp>,
+}
+
+/// Apply `ops` to a fresh runtime, checking invariants after each step.
+///
+/// Episode-local node indices wrap modulo the nodes created so far; ops that
+/// reference a node before any exists are skipped.
+fn run_ops(ops: &[EpisodeOp]) -> Result<(), String> {
+    let mut rt = StubRuntime::new();
+    let mut created: Vec<u64> = Vec::new();
+    for (step, op) in ops.iter().enumerate() {
+        let node_at = |local: usize| created.get(local % created.len().max(1)).copied();
+        match *op {
+            EpisodeOp::CreateNode { belief, energy } => {
+                created.push(rt.create_node(belief, energy).id);
+            }
+            EpisodeOp::MutateNode { node, delta } => {
+                if let Some(id) = node_at(node) {
+                    rt.mutate_node(id, delta);
+                }
+            }
+            EpisodeOp::BindEdge { src, dst, weight } => {
+                if let (Some(src), Some(dst)) = (node_at(src), node_at(dst)) {
+                    rt.bind_edge(src, dst, weight);
+                }
+            }
+            EpisodeOp::Propagate => {
+                rt.propagate();
+            }
+        }
+        check_invariants(&rt, &created).map_err(|why| format!("step {}: {}", step, why))?;
+    }
+    Ok(())
+}
+
+fn check_invariants(rt: &StubRuntime, created: &[u64]) -> Result<(), String> {
+    for id in created {
+        let node = rt.query_node(*id).ok_or(format!("node {} vanished", id))?;
+        if !(0.0..=1.0).contains(&node.belief) {
+            return Err(format!("belief {} outside [0, 1]", node.belief));
+        }
+        if !(node.energy.is_finite() && node.energy >= 0.0) {
+            return Err(format!("energy {} not finite and >= 0", node.energy));
+        }
+    }
+    for (index, entry) in rt.lineage_entries().iter().enumerate() {
+        if entry.sequence != index as u64 {
+            return Err(format!("lineage gap at {}", index));
+        }
+    }
+    Ok(())
+}
+
+fn op_strategy() -> impl Strategy<Value = EpisodeOp> {
+    let value = prop_oneof![
+        -2.0..2.0f64,
+        Just(f64::MAX),
+        Just(f64::MIN),
+        Just(f64::MIN_POSITIVE),
+        Just(0.0),
+    ];
+    prop_oneof![
+        (value.clone(), 0.0..1e6f64)
+            .prop_map(|(belief, energy)| EpisodeOp::CreateNode { belief, energy }),
+        (0..8usize, value.clone()).prop_map(|(node, delta)| EpisodeOp::MutateNode { node, delta }),
+        (0..8usize, 0..8usize, value).prop_map(|(src, dst, weight)| EpisodeOp::BindEdge {
+            src,
+            dst,
+            weight
+        }),
+        Just(EpisodeOp::Propagate),
+    ]
+}
+
+/// Save a minimized failing sequence; the file name is derived from its content
+fn save_to_corpus(dir: &Path, reason: &str, ops: Vec<EpisodeOp>) -> PathBuf {
+    let case = CorpusCase {
+        description: format!("mcp_fuzz_scenarios failure: {}", reason),
+        ops,
+    };
+    let json = serde_json::to_string_pretty(&case).expect("corpus case serializes");
+    let digest = format!("{:x}", Sha256::digest(json.as_bytes()));
+    let path = dir.join(format!("fuzz-{}.json", &digest[..12]));
+    std::fs::write(&path, json + "\n").expect("write corpus file");
+    path
+}
+
+fn load_corpus(dir: &Path) -> Vec<(PathBuf, CorpusCase)> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .expect("corpus directory exists")
+        .map(|entry| entry.expect("readable corpus entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let raw = std::fs::read_to_string(&path).expect("readable corpus file");
+            let case = serde_json::from_str(&raw)
+                .unwrap_or_else(|err| panic!("{}: invalid corpus case: {}", path.display(), err));
+            (path, case)
+        })
+        .collect()
+}
+
+#[test]
+fn mcp_fuzz_scenarios() {
+    let mut runner = TestRunner::new(Config {
+        cases: 128,
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&prop::collection::vec(op_strategy(), 0..32), |ops| {
+        run_ops(&ops).map_err(TestCaseError::fail)
+    });
+    match result {
+        Ok(()) => {}
+        Err(TestError::Fail(reason, ops)) => {
+            let path = save_to_corpus(Path::new(CORPUS_DIR), &reason.to_string(), ops);
+            panic!("fuzz failure saved to {}: {}", path.display(), reason);
+        }
+        Err(err) => panic!("fuzzing aborted: {}", err),
+    }
+}
+
+#[test]
+fn replay_corpus() {
+    let corpus = load_corpus(Path::new(CORPUS_DIR));
+    assert!(!corpus.is_empty(), "corpus must not be empty");
+    for (path, case) in corpus {
+        if let Err(why) = run_ops(&case.ops) {
+            panic!("{} ({}): {}", path.display(), case.description, why);
+        }
+    }
+}
+
+#[test]
+fn episode_ops_roundtrip_through_corpus_files() {
+    let dir = std::env::temp_dir().join(format!("iter-corpus-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let ops = vec![
+        EpisodeOp::CreateNode {
+            belief: 0.25,
+            energy: 2.0,
+        },
+        EpisodeOp::MutateNode {
+            node: 0,
+            delta: f64::MAX,
+        },
+        EpisodeOp::Propagate,
+    ];
+
+    let path = save_to_corpus(&dir, "example", ops.clone());
+    let loaded = load_corpus(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].0, path);
+    assert_eq!(
+        serde_json::to_value(&loaded[0].1.ops).unwrap(),
+        serde_json::to_value(&ops).unwrap()
+    );
+}
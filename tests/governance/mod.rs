@@ -9,6 +9,8 @@
 //! - `error_taxonomy`: Error code completeness and stability
 //! - `versioning`: Protocol version and compatibility rules
 //! - `release_discipline`: Release policy and compatibility invariants
+//! - `stdio_diagnostics`: Server stderr does not leak host details by default
+//! - `fuzz_corpus`: Random op sequences keep state invariants; saved failures replay
 //!
 //! # Governance Contract
 //!
@@ -16,6 +18,8 @@
 //! invariant checks that enforce the public API contract.
 
 pub mod error_taxonomy;
+pub mod fuzz_corpus;
 pub mod release_discipline;
 pub mod schema_stability;
+pub mod stdio_diagnostics;
 pub mod versioning;
@@ -3,7 +3,7 @@
 //! These tests enforce release discipline rules that can be verified at compile/test time.
 //! Policy rules that require runtime or CI enforcement are documented in RELEASE.md.
 
-use iter_mcp_server::types::{ProtocolVersion, PROTOCOL_VERSION};
+use iter_mcp_server::types::{ProtocolVersion, DEPRECATIONS, PROTOCOL_VERSION};
 
 // ============================================================================
 // Protocol Version Invariants
@@ -109,15 +109,23 @@ fn breaking_changes_require_major_bump() {
 /// Deprecated features must have at least one minor version warning period
 #[test]
 fn deprecation_warning_period() {
-    // This test documents the policy - actual deprecations are tracked in CHANGELOG.md
-    // Rule: deprecated in X.Y, removable in X.(Y+1) at earliest, or (X+1).0
-
-    // No deprecated features in 1.0.0
+    // Rule: deprecated in X.Y, removable in X.(Y+1) at earliest, or (X+1).0,
+    // and never removed before the current version ships
     let current = ProtocolVersion::parse(PROTOCOL_VERSION).unwrap();
-    assert!(
-        current.minor == 0 || current.major > 1,
-        "First minor version - no deprecations possible yet"
-    );
+    for entry in DEPRECATIONS {
+        let since = ProtocolVersion::parse(entry.deprecated_in).unwrap();
+        let removal = ProtocolVersion::parse(entry.removed_in).unwrap();
+        assert!(
+            (removal.major, removal.minor) > (since.major, since.minor),
+            "{} needs at least one minor version of warning",
+            entry.item
+        );
+        assert!(
+            (removal.major, removal.minor) > (current.major, current.minor),
+            "{} is past its removal version",
+            entry.item
+        );
+    }
 }
 
 // ============================================================================
@@ -159,16 +167,18 @@ const EOL_ANNOUNCEMENT_MONTHS: u32 = 3;
 
 #[test]
 fn support_window_is_reasonable() {
-    assert!(
-        SUPPORT_WINDOW_MONTHS >= 6,
-        "Support window must be at least 6 months"
-    );
-    assert!(
-        EOL_ANNOUNCEMENT_MONTHS >= 3,
-        "EOL must be announced at least 3 months in advance"
-    );
-    assert!(
-        EOL_ANNOUNCEMENT_MONTHS <= SUPPORT_WINDOW_MONTHS,
-        "EOL announcement must be within support window"
-    );
+    const {
+        assert!(
+            SUPPORT_WINDOW_MONTHS >= 6,
+            "Support window must be at least 6 months"
+        );
+        assert!(
+            EOL_ANNOUNCEMENT_MONTHS >= 3,
+            "EOL must be announced at least 3 months in advance"
+        );
+        assert!(
+            EOL_ANNOUNCEMENT_MONTHS <= SUPPORT_WINDOW_MONTHS,
+            "EOL announcement must be within support window"
+        );
+    }
 }
@@ -119,6 +119,7 @@ fn mcp_node_state_has_all_fields() {
         energy: 1.0,
         esv_valid: true,
         stability: 0.9,
+        locked: false,
     };
 
     let serialized = serde_json::to_value(&node).expect("should serialize");
@@ -129,6 +130,7 @@ fn mcp_node_state_has_all_fields() {
     assert!(serialized.get("energy").is_some());
     assert!(serialized.get("esv_valid").is_some());
     assert!(serialized.get("stability").is_some());
+    assert!(serialized.get("locked").is_some());
 }
 
 #[test]
@@ -169,6 +171,43 @@ fn mcp_governor_status_has_all_fields() {
     assert!(serialized.get("healthy").is_some());
 }
 
+#[test]
+fn mcp_esv_audit_has_all_fields() {
+    let audit = McpEsvAudit {
+        node_id: 3,
+        valid: true,
+        compliance_status: "compliant".to_string(),
+        violated_constraints: Vec::new(),
+        margin: None,
+    };
+
+    let serialized = serde_json::to_value(&audit).expect("should serialize");
+
+    assert!(serialized.get("node_id").is_some());
+    assert!(serialized.get("valid").is_some());
+    assert!(serialized.get("compliance_status").is_some());
+    assert_eq!(serialized.as_object().unwrap().len(), 3);
+}
+
+#[test]
+fn mcp_esv_audit_roundtrip() {
+    let original = McpEsvAudit {
+        node_id: 7,
+        valid: false,
+        compliance_status: "non_compliant".to_string(),
+        violated_constraints: vec!["belief_bounds".to_string()],
+        margin: Some(-0.25),
+    };
+    let json_str = serde_json::to_string(&original).expect("serialize");
+    let parsed: McpEsvAudit = serde_json::from_str(&json_str).expect("deserialize");
+
+    assert_eq!(parsed.node_id, original.node_id);
+    assert_eq!(parsed.valid, original.valid);
+    assert_eq!(parsed.compliance_status, original.compliance_status);
+    assert_eq!(parsed.violated_constraints, original.violated_constraints);
+    assert_eq!(parsed.margin, original.margin);
+}
+
 #[test]
 fn mcp_lineage_entry_has_all_fields() {
     let entry = McpLineageEntry {
@@ -176,6 +215,8 @@ fn mcp_lineage_entry_has_all_fields() {
         operation: "tick".to_string(),
         checksum: "abc123".to_string(),
         tick: 100,
+        detail: None,
+        timestamp_unix_ms: None,
     };
 
     let serialized = serde_json::to_value(&entry).expect("should serialize");
@@ -184,6 +225,29 @@ fn mcp_lineage_entry_has_all_fields() {
     assert!(serialized.get("operation").is_some());
     assert!(serialized.get("checksum").is_some());
     assert!(serialized.get("tick").is_some());
+    assert!(
+        serialized.get("detail").is_none(),
+        "absent detail must not be serialized"
+    );
+    assert!(
+        serialized.get("timestamp_unix_ms").is_none(),
+        "absent timestamp must not be serialized"
+    );
+}
+
+#[test]
+fn mcp_lineage_entry_detail_is_optional_on_the_wire() {
+    let legacy = json!({"sequence": 1, "operation": "tick", "checksum": "abc123", "tick": 100});
+    let parsed: McpLineageEntry = serde_json::from_value(legacy).expect("should deserialize");
+    assert!(parsed.detail.is_none());
+    assert!(parsed.timestamp_unix_ms.is_none());
+
+    let labelled = McpLineageEntry {
+        detail: Some("node.create".to_string()),
+        ..parsed
+    };
+    let serialized = serde_json::to_value(&labelled).expect("should serialize");
+    assert_eq!(serialized["detail"], "node.create");
 }
 
 // ============================================================================
@@ -234,4 +298,10 @@ fn export_lineage_params_schema() {
     let valid = json!({"path": "/tmp/lineage.json"});
     let params: ExportLineageParams = serde_json::from_value(valid).expect("should parse");
     assert_eq!(params.path, "/tmp/lineage.json");
+    assert_eq!(params.compress, None);
+
+    let compressed: ExportLineageParams =
+        serde_json::from_value(json!({"path": "lineage.json.gz", "compress": true}))
+            .expect("should parse");
+    assert_eq!(compressed.compress, Some(true));
 }
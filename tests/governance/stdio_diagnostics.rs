@@ -0,0 +1,32 @@
+//! Stdio Diagnostics Invariants
+//!
+//! The server binary must not leak host details (executable path, CWD)
+//! to stderr unless startup banners are explicitly enabled.
+
+use std::process::{Command, Stdio};
+
+fn run_server_stderr(print_banner: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_iter-server"))
+        .env("SCG_PRINT_BANNER", print_banner)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .expect("server binary must run");
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn banner_off_emits_no_path_or_cwd() {
+    let stderr = run_server_stderr("0");
+    assert!(!stderr.contains("PATH ="), "path leaked: {}", stderr);
+    assert!(!stderr.contains("CWD ="), "cwd leaked: {}", stderr);
+    assert!(!stderr.contains("PUBLIC STUB MODE"));
+}
+
+#[test]
+fn banner_on_restores_diagnostics() {
+    let stderr = run_server_stderr("1");
+    assert!(stderr.contains("ITER LOCAL PROOF — PATH ="));
+    assert!(stderr.contains("PUBLIC STUB MODE"));
+}